@@ -4,6 +4,7 @@ use std::collections::VecDeque;
 use std::fs;
 
 use md5::{Digest, Md5};
+use miner::{decimal, encode_hex};
 
 fn parse_input(input: &str) -> (String, usize) {
     let mut lines = input.lines();
@@ -13,27 +14,35 @@ fn parse_input(input: &str) -> (String, usize) {
     )
 }
 
-fn hash(input: &str) -> String {
-    let mut hasher = Md5::new();
-    hasher.update(input);
-    format!("{:02x}", hasher.finalize())
+fn hash(salt: &Md5, index: usize) -> [u8; 16] {
+    let mut buf = [0; 20];
+    salt.clone()
+        .chain_update(decimal(index, &mut buf))
+        .finalize()
+        .into()
 }
 
-fn streched_hash(input: &str) -> String {
-    let mut current_hash;
-    let mut hasher = Md5::new();
-    hasher.update(input);
-    current_hash = hasher.finalize();
+fn streched_hash(salt: &Md5, index: usize) -> [u8; 16] {
+    let mut current_hash = hash(salt, index);
+    let mut hex = [0; 32];
     for _ in 0..2016 {
-        let mut hasher = Md5::new();
-        hasher.update(format!("{:02x}", current_hash));
-        current_hash = hasher.finalize();
+        encode_hex(&current_hash, &mut hex);
+        current_hash = Md5::digest(hex).into();
     }
-    format!("{:02x}", current_hash)
+    current_hash
 }
 
-fn potential_key(hash: &str) -> Option<char> {
-    for window in hash.chars().collect::<Vec<char>>().windows(3) {
+fn nibbles(digest: &[u8; 16]) -> [u8; 32] {
+    let mut retval = [0; 32];
+    for (idx, byte) in digest.iter().enumerate() {
+        retval[idx * 2] = byte >> 4;
+        retval[idx * 2 + 1] = byte & 0x0f;
+    }
+    retval
+}
+
+fn potential_key(hash: &[u8; 16]) -> Option<u8> {
+    for window in nibbles(hash).windows(3) {
         if window[0] == window[1] && window[0] == window[2] {
             return Some(window[0]);
         }
@@ -41,32 +50,40 @@ fn potential_key(hash: &str) -> Option<char> {
     None
 }
 
-fn is_key(items: &VecDeque<(usize, String)>, desired: char) -> bool {
-    let test = format!("{desired}{desired}{desired}{desired}{desired}");
-    for item in items {
-        if item.1.contains(&test) {
-            return true;
+fn quintuples(hash: &[u8; 16]) -> u16 {
+    let mut retval = 0;
+    for window in nibbles(hash).windows(5) {
+        if window.iter().all(|n| *n == window[0]) {
+            retval |= 1 << window[0];
         }
     }
-    false
+    retval
 }
 
-fn part1(salt: &str, desired_count: usize) -> usize {
+fn is_key(items: &VecDeque<(usize, Option<u8>, u16)>, desired: u8) -> bool {
+    items.iter().any(|item| item.2 & (1 << desired) != 0)
+}
+
+fn find_key(salt: &str, desired_count: usize, hasher: fn(&Md5, usize) -> [u8; 16]) -> usize {
+    let salt = Md5::new_with_prefix(salt);
+    let entry = |index: usize| {
+        let hash = hasher(&salt, index);
+        (index, potential_key(&hash), quintuples(&hash))
+    };
     let mut queue = VecDeque::with_capacity(1000);
     let mut current_index = 0;
     let mut queue_index = 0;
     let mut count = 0;
     for _ in 0..1000 {
-        queue.push_back((queue_index, hash(&format!("{}{}", salt, queue_index))));
+        queue.push_back(entry(queue_index));
         queue_index += 1;
     }
     while count < desired_count {
-        queue.push_back((queue_index, hash(&format!("{}{}", salt, queue_index))));
+        queue.push_back(entry(queue_index));
         queue_index += 1;
         let pop = queue.pop_front().unwrap();
         current_index = pop.0;
-        let test = pop.1;
-        if let Some(c) = potential_key(&test) {
+        if let Some(c) = pop.1 {
             if is_key(&queue, c) {
                 count += 1;
             }
@@ -75,28 +92,12 @@ fn part1(salt: &str, desired_count: usize) -> usize {
     current_index
 }
 
+fn part1(salt: &str, desired_count: usize) -> usize {
+    find_key(salt, desired_count, hash)
+}
+
 fn part2(salt: &str, desired_count: usize) -> usize {
-    let mut queue = VecDeque::with_capacity(1000);
-    let mut current_index = 0;
-    let mut queue_index = 0;
-    let mut count = 0;
-    for _ in 0..1000 {
-        queue.push_back((queue_index, streched_hash(&format!("{}{}", salt, queue_index))));
-        queue_index += 1;
-    }
-    while count < desired_count {
-        queue.push_back((queue_index, streched_hash(&format!("{}{}", salt, queue_index))));
-        queue_index += 1;
-        let pop = queue.pop_front().unwrap();
-        current_index = pop.0;
-        let test = pop.1;
-        if let Some(c) = potential_key(&test) {
-            if is_key(&queue, c) {
-                count += 1;
-            }
-        }
-    }
-    current_index
+    find_key(salt, desired_count, streched_hash)
 }
 
 fn main() {
@@ -123,4 +124,23 @@ mod tests {
         let (salt, desired_count) = parse_input(&input);
         assert_eq!(22551, part2(&salt, desired_count));
     }
+
+    #[test]
+    fn test_hashes() {
+        let salt = Md5::new_with_prefix("abc");
+        let mut hex = [0; 32];
+        encode_hex(&hash(&salt, 18), &mut hex);
+        assert_eq!(b"0034e0923cc38887a57bd7b1d4f953df", &hex);
+        assert_eq!(Some(8), potential_key(&hash(&salt, 18)));
+        assert_eq!(1 << 0xe, quintuples(&hash(&salt, 816)));
+        encode_hex(&streched_hash(&salt, 0), &mut hex);
+        assert_eq!(b"a107ff634856bb300138cac6568c0f24", &hex);
+    }
+
+    #[test]
+    fn test_first_keys() {
+        assert_eq!(39, part1("abc", 1));
+        assert_eq!(22728, part1("abc", 64));
+        assert_eq!(10, part2("abc", 1));
+    }
 }
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
 use std::fs;
 
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -7,6 +8,10 @@ enum Direction {
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
 }
 
 impl Direction {
@@ -19,232 +24,296 @@ impl Direction {
             _ => panic!("at the disco"),
         }
     }
-}
 
-#[derive(Debug, Clone)]
-struct Button {
-    id: usize,
-    neighbours: HashMap<Direction, usize>,
-}
+    fn combine(self, other: Direction) -> Option<Direction> {
+        match (self, other) {
+            (Direction::Up, Direction::Left) => Some(Direction::UpLeft),
+            (Direction::Up, Direction::Right) => Some(Direction::UpRight),
+            (Direction::Down, Direction::Left) => Some(Direction::DownLeft),
+            (Direction::Down, Direction::Right) => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
 
-impl Button {
-    fn new(id: usize) -> Self {
-        let mut neighbours = HashMap::new();
-        match id {
-            1 => {
-                neighbours.insert(Direction::Right, 2);
-                neighbours.insert(Direction::Down, 4);
-            }
-            2 => {
-                neighbours.insert(Direction::Right, 3);
-                neighbours.insert(Direction::Left, 1);
-                neighbours.insert(Direction::Down, 5);
-            }
-            3 => {
-                neighbours.insert(Direction::Left, 2);
-                neighbours.insert(Direction::Down, 6);
-            }
-            4 => {
-                neighbours.insert(Direction::Up, 1);
-                neighbours.insert(Direction::Right, 5);
-                neighbours.insert(Direction::Down, 7);
-            }
-            5 => {
-                neighbours.insert(Direction::Up, 2);
-                neighbours.insert(Direction::Right, 6);
-                neighbours.insert(Direction::Down, 8);
-                neighbours.insert(Direction::Left, 4);
-            }
-            6 => {
-                neighbours.insert(Direction::Up, 3);
-                neighbours.insert(Direction::Left, 5);
-                neighbours.insert(Direction::Down, 9);
-            }
-            7 => {
-                neighbours.insert(Direction::Right, 8);
-                neighbours.insert(Direction::Up, 4);
-            }
-            8 => {
-                neighbours.insert(Direction::Right, 9);
-                neighbours.insert(Direction::Left, 7);
-                neighbours.insert(Direction::Up, 5);
-            }
-            9 => {
-                neighbours.insert(Direction::Left, 8);
-                neighbours.insert(Direction::Up, 6);
+    fn parse_line(line: &str, diagonals: bool) -> Vec<Direction> {
+        let mut retval = Vec::new();
+        let mut chars = line.chars().map(Direction::from).peekable();
+        while let Some(dir) = chars.next() {
+            if diagonals {
+                if let Some(diagonal) = chars.peek().and_then(|next| dir.combine(*next)) {
+                    chars.next();
+                    retval.push(diagonal);
+                    continue;
+                }
             }
-            _ => panic!("at the disco"),
+            retval.push(dir);
         }
-        Button { id, neighbours }
+        retval
     }
 
-    fn advance(&self, direction: Direction) -> usize {
-        match self.neighbours.get(&direction) {
-            None => self.id,
-            Some(id) => *id
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+            Direction::UpLeft => "UL",
+            Direction::UpRight => "UR",
+            Direction::DownLeft => "DL",
+            Direction::DownRight => "DR",
         }
     }
-}
-
-#[derive(Debug, Clone)]
-struct WonkyButton {
-    id: char,
-    neighbours: HashMap<Direction, char>,
-}
-
-impl WonkyButton {
-    fn new(id: char) -> Self {
-        let mut neighbours = HashMap::new();
-        match id {
-            '1' => {
-                neighbours.insert(Direction::Down, '3');
-            }
-            '2' => {
-                neighbours.insert(Direction::Right, '3');
-                neighbours.insert(Direction::Down, '6');
-            }
-            '3' => {
-                neighbours.insert(Direction::Up, '1');
-                neighbours.insert(Direction::Down, '7');
-                neighbours.insert(Direction::Right, '4');
-                neighbours.insert(Direction::Left, '2');
-            }
-            '4' => {
-                neighbours.insert(Direction::Left, '3');
-                neighbours.insert(Direction::Down, '8');
-            }
-            '5' => {
-                neighbours.insert(Direction::Right, '6');
-            }
-            '6' => {
-                neighbours.insert(Direction::Up, '2');
-                neighbours.insert(Direction::Left, '5');
-                neighbours.insert(Direction::Down, 'A');
-                neighbours.insert(Direction::Right, '7');
 
-            }
-            '7' => {
-                neighbours.insert(Direction::Up, '3');
-                neighbours.insert(Direction::Left, '6');
-                neighbours.insert(Direction::Down, 'B');
-                neighbours.insert(Direction::Right, '8');
-            }
-            '8' => {
-                neighbours.insert(Direction::Up, '4');
-                neighbours.insert(Direction::Left, '7');
-                neighbours.insert(Direction::Down, 'C');
-                neighbours.insert(Direction::Right, '9');
-            }
-            '9' => {
-                neighbours.insert(Direction::Left, '8');
-            }
-            'A' => {
-                neighbours.insert(Direction::Right, 'B');
-                neighbours.insert(Direction::Up, '6');
-            }
-            'B' => {
-                neighbours.insert(Direction::Up, '7');
-                neighbours.insert(Direction::Left, 'A');
-                neighbours.insert(Direction::Down, 'D');
-                neighbours.insert(Direction::Right, 'C');
-            }
-            'C' => {
-                neighbours.insert(Direction::Left, 'B');
-                neighbours.insert(Direction::Up, '8');
-            }
-            'D' => {
-                neighbours.insert(Direction::Up, 'B');
-            }
-            _ => panic!("at the disco"),
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (-1, 1),
+            Direction::DownLeft => (1, -1),
+            Direction::DownRight => (1, 1),
         }
-        WonkyButton { id, neighbours }
     }
 
-    fn advance(&self, direction: Direction) -> char {
-        match self.neighbours.get(&direction) {
-            None => self.id,
-            Some(id) => *id
-        }
+    fn vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    fn horizontal(self) -> bool {
+        matches!(self, Direction::Left | Direction::Right)
     }
 }
 
+const DIRECTIONS: [Direction; 8] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+    Direction::UpLeft,
+    Direction::UpRight,
+    Direction::DownLeft,
+    Direction::DownRight,
+];
+
+const SQUARE: &str = "123\n456\n789";
+
+const WONKY: &str = "  1\n 234\n56789\n ABC\n  D";
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+enum EdgePolicy {
+    Clamp,
+    Wrap,
+    Error,
+}
+
+#[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
+enum KeypadError {
+    UnknownButton(char),
+    OffEdge(char, Direction),
+}
+
 #[derive(Debug, Clone)]
 struct Keypad {
-    buttons: HashMap<usize, Button>,
-    wonky_buttons: HashMap<char, WonkyButton>
+    grid: Vec<Vec<Option<char>>>,
+    positions: HashMap<char, (usize, usize)>,
+    edge: EdgePolicy,
+    diagonals: bool,
 }
 
 impl Keypad {
-    fn new() -> Self {
-        let mut buttons = HashMap::new();
-        for i in 1usize..=9 {
-            buttons.insert(i, Button::new(i));
+    fn new(layout: &str, edge: EdgePolicy, diagonals: bool) -> Self {
+        let mut grid = Vec::new();
+        let mut positions = HashMap::new();
+        for (y, line) in layout.lines().enumerate() {
+            let mut row = Vec::new();
+            for (x, c) in line.chars().enumerate() {
+                if c == ' ' {
+                    row.push(None);
+                } else {
+                    positions.insert(c, (y, x));
+                    row.push(Some(c));
+                }
+            }
+            grid.push(row);
         }
-
-        let mut wonky_buttons = HashMap::new();
-        let chars = ['1', '2', '3', '4', '5', '6', '7', '8', '9', 'A', 'B', 'C', 'D'];
-        for c in chars {
-            wonky_buttons.insert(c, WonkyButton::new(c));
+        let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut grid {
+            row.resize(width, None);
         }
+        Keypad {
+            grid,
+            positions,
+            edge,
+            diagonals,
+        }
+    }
+
+    fn square() -> Self {
+        Keypad::new(SQUARE, EdgePolicy::Clamp, false)
+    }
 
-        Keypad { buttons, wonky_buttons }
+    fn wonky() -> Self {
+        Keypad::new(WONKY, EdgePolicy::Clamp, false)
     }
 
-    fn advance(&self, button: usize, direction: Direction) -> usize {
-        self.buttons.get(&button).unwrap().advance(direction)
+    fn directions(&self) -> &'static [Direction] {
+        if self.diagonals {
+            &DIRECTIONS
+        } else {
+            &DIRECTIONS[..4]
+        }
     }
 
-    fn advance_wonky(&self, button: char, direction: Direction) -> char {
-        self.wonky_buttons.get(&button).unwrap().advance(direction)
+    fn get(&self, y: isize, x: isize) -> Option<char> {
+        if y < 0 || x < 0 {
+            return None;
+        }
+        *self.grid.get(y as usize)?.get(x as usize)?
     }
-}
 
-fn part1(input: &str) -> usize {
-    let keypad = Keypad::new();
-    let mut inst = Vec::new();
-    for line in input.lines() {
-        let mut set = Vec::new();
-        for char in line.chars() {
-            set.push(Direction::from(char));
+    fn advance(&self, button: char, direction: Direction) -> Result<char, KeypadError> {
+        let &(y, x) = self
+            .positions
+            .get(&button)
+            .ok_or(KeypadError::UnknownButton(button))?;
+        let (dy, dx) = direction.delta();
+        let (mut y, mut x) = (y as isize + dy, x as isize + dx);
+        if let Some(next) = self.get(y, x) {
+            return Ok(next);
+        }
+        match self.edge {
+            EdgePolicy::Clamp => Ok(button),
+            EdgePolicy::Error => Err(KeypadError::OffEdge(button, direction)),
+            EdgePolicy::Wrap => {
+                let height = self.grid.len() as isize;
+                let width = self.grid[0].len() as isize;
+                loop {
+                    y = y.rem_euclid(height);
+                    x = x.rem_euclid(width);
+                    if let Some(next) = self.get(y, x) {
+                        return Ok(next);
+                    }
+                    y += dy;
+                    x += dx;
+                }
+            }
         }
-        inst.push(set);
     }
-    let mut code = Vec::new();
-    let mut current = 5;
-    for line in inst {
-        for dir in line {
-            current = keypad.advance(current, dir);
+
+    fn enter(&self, start: char, input: &str) -> Result<String, KeypadError> {
+        self.enter_lines(start, input.lines())
+    }
+
+    fn enter_lines<'a, I>(&self, start: char, lines: I) -> Result<String, KeypadError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut code = String::new();
+        let mut current = start;
+        for line in lines {
+            for dir in Direction::parse_line(line, self.diagonals) {
+                current = self.advance(current, dir)?;
+            }
+            code.push(current);
         }
-        code.push(current.to_string())
+        Ok(code)
     }
-    code.join("").parse::<usize>().unwrap()
-}
 
-fn part2(input: &str) -> String {
-    let keypad = Keypad::new();
-    let mut inst = Vec::new();
-    for line in input.lines() {
-        let mut set = Vec::new();
-        for char in line.chars() {
-            set.push(Direction::from(char));
+    fn shortest_path(&self, start: char, target: char) -> Option<Vec<Direction>> {
+        let mut seen = HashSet::new();
+        let mut queue = VecDeque::new();
+        seen.insert((start, false));
+        queue.push_back((start, false, Vec::new()));
+        while let Some((current, after_vertical, path)) = queue.pop_front() {
+            if current == target {
+                return Some(path);
+            }
+            for &dir in self.directions() {
+                if self.diagonals && after_vertical && dir.horizontal() {
+                    continue;
+                }
+                let Ok(next) = self.advance(current, dir) else {
+                    continue;
+                };
+                if seen.insert((next, dir.vertical())) {
+                    let mut next_path = path.clone();
+                    next_path.push(dir);
+                    queue.push_back((next, dir.vertical(), next_path));
+                }
+            }
         }
-        inst.push(set);
+        None
     }
-    let mut code = Vec::new();
-    let mut current = '5';
-    for line in inst {
-        for dir in line {
-            current = keypad.advance_wonky(current, dir);
+
+    fn instructions(&self, start: char, code: &str) -> Option<Vec<String>> {
+        let mut lines = Vec::new();
+        let mut current = start;
+        for target in code.chars() {
+            if !self.positions.contains_key(&target) {
+                return None;
+            }
+            let path = self.shortest_path(current, target)?;
+            lines.push(path.into_iter().map(Direction::as_str).collect::<String>());
+            current = target;
         }
-        code.push(current.to_string())
+        Some(lines)
     }
-    code.join("")
+}
+
+fn part1(input: &str) -> usize {
+    Keypad::square()
+        .enter('5', input)
+        .unwrap()
+        .parse::<usize>()
+        .unwrap()
+}
+
+fn part2(input: &str) -> String {
+    Keypad::wonky().enter('5', input).unwrap()
 }
 
 fn main() {
+    let mut edge = EdgePolicy::Clamp;
+    let mut diagonals = false;
+    let mut code = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--wrap" => edge = EdgePolicy::Wrap,
+            "--error" => edge = EdgePolicy::Error,
+            "--diagonals" => diagonals = true,
+            _ => code = Some(arg),
+        }
+    }
+    let keypads = [
+        ("keypad", Keypad::new(SQUARE, edge, diagonals)),
+        ("wonky keypad", Keypad::new(WONKY, edge, diagonals)),
+    ];
+    if let Some(code) = code {
+        for (name, keypad) in &keypads {
+            match keypad.instructions('5', &code) {
+                Some(inst) => {
+                    println!("{}:", name);
+                    inst.iter().for_each(|line| println!("{}", line));
+                }
+                None => println!("{} cannot be entered on the {}", code, name),
+            }
+        }
+        return;
+    }
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
-    println!("{}", part1(&input));
-    println!("{}", part2(&input));
+    if edge == EdgePolicy::Clamp && !diagonals {
+        println!("{}", part1(&input));
+        println!("{}", part2(&input));
+        return;
+    }
+    for (name, keypad) in &keypads {
+        match keypad.enter('5', &input) {
+            Ok(code) => println!("{}", code),
+            Err(err) => println!("{} failed: {:?}", name, err),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -262,4 +331,101 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!("5DB3", part2(&input));
     }
+
+    #[test]
+    fn test_instructions() {
+        let keypad = Keypad::square();
+        let inst = keypad.instructions('5', "1985").unwrap();
+        assert_eq!(vec!["UL", "DDRR", "L", "U"], inst);
+        assert_eq!(1985, part1(&inst.join("\n")));
+        assert_eq!(None, keypad.instructions('5', "1A"));
+    }
+
+    #[test]
+    fn test_instructions_wonky() {
+        let keypad = Keypad::wonky();
+        let inst = keypad.instructions('5', "5DB3").unwrap();
+        assert_eq!("5DB3", part2(&inst.join("\n")));
+        assert_eq!(
+            vec![0, 4, 1, 2],
+            inst.iter().map(String::len).collect::<Vec<usize>>()
+        );
+        assert_eq!(None, keypad.instructions('5', "0"));
+    }
+
+    #[test]
+    fn test_diagonals() {
+        let keypad = Keypad::new(SQUARE, EdgePolicy::Clamp, true);
+        assert_eq!(Ok("1".to_string()), keypad.enter('5', "UL"));
+        assert_eq!(Ok("9".to_string()), keypad.enter('5', "DRDR"));
+        assert_eq!(Ok("4".to_string()), keypad.enter('5', "ULD"));
+        assert_eq!(Ok("8".to_string()), keypad.enter('5', "LDR"));
+        let inst = keypad.instructions('5', "1937").unwrap();
+        assert_eq!(vec!["UL", "DRDR", "UU", "DLDL"], inst);
+        assert_eq!(
+            Ok("1937".to_string()),
+            keypad.enter_lines('5', inst.iter().map(String::as_str))
+        );
+    }
+
+    #[test]
+    fn test_wrap() {
+        let keypad = Keypad::new(SQUARE, EdgePolicy::Wrap, false);
+        assert_eq!(Ok('7'), keypad.advance('1', Direction::Up));
+        assert_eq!(Ok('4'), keypad.advance('6', Direction::Right));
+        let keypad = Keypad::new(WONKY, EdgePolicy::Wrap, false);
+        assert_eq!(Ok('5'), keypad.advance('5', Direction::Up));
+        assert_eq!(Ok('D'), keypad.advance('1', Direction::Up));
+        assert_eq!(Ok('2'), keypad.advance('4', Direction::Right));
+        let keypad = Keypad::new(WONKY, EdgePolicy::Wrap, true);
+        assert_eq!(Ok('9'), keypad.advance('1', Direction::UpLeft));
+        let inst = keypad.instructions('5', "D19").unwrap();
+        assert_eq!(
+            Ok("D19".to_string()),
+            keypad.enter_lines('5', inst.iter().map(String::as_str))
+        );
+    }
+
+    #[test]
+    fn test_error() {
+        let keypad = Keypad::new(SQUARE, EdgePolicy::Error, false);
+        assert_eq!(Ok("2".to_string()), keypad.enter('5', "U"));
+        assert_eq!(
+            Err(KeypadError::OffEdge('2', Direction::Up)),
+            keypad.enter('5', "UU")
+        );
+        assert_eq!(
+            Err(KeypadError::UnknownButton('X')),
+            keypad.advance('X', Direction::Up)
+        );
+        assert_eq!(Some(vec!["UL".to_string()]), keypad.instructions('5', "1"));
+    }
+
+    #[test]
+    fn test_repeated_digits() {
+        let keypad = Keypad::square();
+        let inst = keypad.instructions('5', "155").unwrap();
+        assert_eq!(vec!["UL", "DR", ""], inst);
+        assert_eq!(vec![""], keypad.instructions('5', "5").unwrap());
+        for (keypad, code) in [
+            (Keypad::square(), "155"),
+            (Keypad::square(), "5"),
+            (Keypad::square(), "1111"),
+            (Keypad::square(), "99599"),
+            (Keypad::wonky(), "77DD5"),
+            (Keypad::new(WONKY, EdgePolicy::Wrap, true), "D1199"),
+        ] {
+            let inst = keypad.instructions('5', code).unwrap();
+            assert_eq!(
+                Ok(code.to_string()),
+                keypad.enter_lines('5', inst.iter().map(String::as_str))
+            );
+            let text = inst
+                .iter()
+                .map(|line| format!("{}\n", line))
+                .collect::<String>();
+            assert_eq!(Ok(code.to_string()), keypad.enter('5', &text));
+        }
+        assert_eq!(155, part1("UL\nDR\n\n"));
+    }
 }
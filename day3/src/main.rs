@@ -1,32 +1,70 @@
+mod polygon;
+
 use std::fs;
 
-fn parse_input(input: &str) -> Vec<[usize; 3]> {
-    let mut retval = Vec::new();
-    for line in input.lines() {
-        let mut splits = line.split_whitespace();
-        retval.push([
-            splits.next().unwrap().parse::<usize>().unwrap(),
-            splits.next().unwrap().parse::<usize>().unwrap(),
-            splits.next().unwrap().parse::<usize>().unwrap(),
-        ])
+use polygon::Report;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum ParseError {
+    InvalidNumber(usize, String),
+    WrongColumnCount(usize, usize, usize),
+    RaggedBlock(usize, usize),
+}
+
+fn read_rows(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+    let mut rows: Vec<Vec<u64>> = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let mut row = Vec::new();
+        for split in line.split_whitespace() {
+            match split.parse::<u64>() {
+                Ok(val) => row.push(val),
+                Err(_) => return Err(ParseError::InvalidNumber(idx, split.to_string())),
+            }
+        }
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                return Err(ParseError::WrongColumnCount(idx, first.len(), row.len()));
+            }
+        }
+        rows.push(row);
     }
-    retval
+    Ok(rows)
 }
 
-fn is_valid_triangle(sides: [usize; 3]) -> bool {
-    if (sides[0] + sides[1] <= sides[2])
-        || (sides[0] + sides[2] <= sides[1])
-        || (sides[1] + sides[2] <= sides[0])
-    {
-        false
-    } else {
-        true
+fn parse_input<const N: usize>(input: &str, transpose: bool) -> Result<Vec<[u64; N]>, ParseError> {
+    let rows = read_rows(input)?;
+    let mut retval = Vec::new();
+    if !transpose {
+        for (idx, row) in rows.iter().enumerate() {
+            match row.as_slice().try_into() {
+                Ok(shape) => retval.push(shape),
+                Err(_) => return Err(ParseError::WrongColumnCount(idx, N, row.len())),
+            }
+        }
+        return Ok(retval);
+    }
+    for block in rows.chunks(N) {
+        if block.len() != N {
+            return Err(ParseError::RaggedBlock(block.len(), N));
+        }
+        for col in 0..block[0].len() {
+            let mut shape = [0; N];
+            for (side, row) in shape.iter_mut().zip(block) {
+                *side = row[col];
+            }
+            retval.push(shape);
+        }
     }
+    Ok(retval)
+}
+
+fn is_valid_triangle(sides: [u64; 3]) -> bool {
+    polygon::is_valid(&sides)
 }
 
 fn part1(input: &str) -> usize {
     let mut valid = 0;
-    for triangle in parse_input(input) {
+    for triangle in parse_input(input, false).unwrap() {
         if is_valid_triangle(triangle) {
             valid += 1;
         }
@@ -34,37 +72,9 @@ fn part1(input: &str) -> usize {
     valid
 }
 
-
-
-fn parse_input_chunks(input: &str) -> Vec<[usize; 3]> {
-    let mut retval = Vec::new();
-    for line in input.lines().collect::<Vec<&str>>().chunks(3) {
-        let mut splits1 = line[0].split_whitespace();
-        let mut splits2 = line[1].split_whitespace();
-        let mut splits3 = line[2].split_whitespace();
-        retval.push([
-            splits1.next().unwrap().parse::<usize>().unwrap(),
-            splits2.next().unwrap().parse::<usize>().unwrap(),
-            splits3.next().unwrap().parse::<usize>().unwrap(),
-        ]);
-        retval.push([
-            splits1.next().unwrap().parse::<usize>().unwrap(),
-            splits2.next().unwrap().parse::<usize>().unwrap(),
-            splits3.next().unwrap().parse::<usize>().unwrap(),
-        ]);
-        retval.push([
-            splits1.next().unwrap().parse::<usize>().unwrap(),
-            splits2.next().unwrap().parse::<usize>().unwrap(),
-            splits3.next().unwrap().parse::<usize>().unwrap(),
-        ])
-    }
-    retval
-}
-
-
 fn part2(input: &str) -> usize {
     let mut valid = 0;
-    for triangle in parse_input_chunks(input) {
+    for triangle in parse_input(input, true).unwrap() {
         if is_valid_triangle(triangle) {
             valid += 1;
         }
@@ -76,6 +86,8 @@ fn main() {
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
     println!("{}", part1(&input));
     println!("{}", part2(&input));
+    println!("{}", Report::new(&parse_input(&input, false).unwrap()));
+    println!("{}", Report::new(&parse_input(&input, true).unwrap()));
 }
 
 #[cfg(test)]
@@ -93,4 +105,75 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!(0, part2(&input));
     }
+
+    #[test]
+    fn test_parse_input() {
+        let input =
+            "101 301 501\n102 302 502\n103 303 503\n201 401 601\n202 402 602\n203 403 603\n";
+        assert_eq!(
+            Ok(vec![[101, 301, 501], [102, 302, 502]]),
+            parse_input::<3>(input, false).map(|shapes| shapes[..2].to_vec())
+        );
+        assert_eq!(
+            Ok(vec![
+                [101, 102, 103],
+                [301, 302, 303],
+                [501, 502, 503],
+                [201, 202, 203],
+                [401, 402, 403],
+                [601, 602, 603],
+            ]),
+            parse_input::<3>(input, true)
+        );
+        assert_eq!(
+            Ok(vec![
+                [101, 102],
+                [301, 302],
+                [501, 502],
+                [103, 201],
+                [303, 401],
+                [503, 601]
+            ]),
+            parse_input::<2>("101 301 501\n102 302 502\n103 303 503\n201 401 601\n", true)
+        );
+    }
+
+    #[test]
+    fn test_parse_input_errors() {
+        assert_eq!(
+            Err(ParseError::RaggedBlock(1, 3)),
+            parse_input::<3>("1 2 3\n4 5 6\n7 8 9\n1 2 3\n", true)
+        );
+        assert_eq!(
+            Err(ParseError::WrongColumnCount(1, 3, 2)),
+            parse_input::<3>("1 2 3\n4 5\n", false)
+        );
+        assert_eq!(
+            Err(ParseError::WrongColumnCount(0, 3, 2)),
+            parse_input::<3>("4 5\n", false)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidNumber(0, "x".to_string())),
+            parse_input::<3>("1 x 3\n", false)
+        );
+    }
+
+    #[test]
+    fn test_parse_big_sides() {
+        assert_eq!(
+            Ok(vec![[u64::MAX, u64::MAX, 1]]),
+            parse_input::<3>("18446744073709551615 18446744073709551615 1\n", false)
+        );
+        assert_eq!(
+            Err(ParseError::InvalidNumber(
+                0,
+                "18446744073709551616".to_string()
+            )),
+            parse_input::<3>("18446744073709551616 1 1\n", false)
+        );
+        let input =
+            "18446744073709551615 18446744073709551615 1\n18446744073709551615 1 5\n1 1 1\n";
+        assert_eq!(2, part1(input));
+        assert_eq!(1, part2(input));
+    }
 }
@@ -0,0 +1,180 @@
+use std::fmt;
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum SideClass {
+    Equilateral,
+    Isosceles,
+    Scalene,
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum AngleClass {
+    Acute,
+    Right,
+    Obtuse,
+}
+
+pub fn is_valid(sides: &[u64]) -> bool {
+    if sides.len() < 3 {
+        return false;
+    }
+    let mut total = 0u128;
+    for &side in sides {
+        match total.checked_add(side as u128) {
+            Some(sum) => total = sum,
+            None => return false,
+        }
+    }
+    let longest = *sides.iter().max().unwrap() as u128;
+    longest < total - longest
+}
+
+pub fn classify(sides: [u64; 3]) -> Option<(SideClass, AngleClass)> {
+    if !is_valid(&sides) {
+        return None;
+    }
+    let mut sorted = sides;
+    sorted.sort();
+    let [a, b, c] = sorted.map(|side| side as u128);
+    let side_class = if a == c {
+        SideClass::Equilateral
+    } else if a == b || b == c {
+        SideClass::Isosceles
+    } else {
+        SideClass::Scalene
+    };
+    let angle_class = match (a * a).checked_add(b * b) {
+        None => AngleClass::Acute,
+        Some(legs) => match (c * c).cmp(&legs) {
+            std::cmp::Ordering::Less => AngleClass::Acute,
+            std::cmp::Ordering::Equal => AngleClass::Right,
+            std::cmp::Ordering::Greater => AngleClass::Obtuse,
+        },
+    };
+    Some((side_class, angle_class))
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Report {
+    pub total: usize,
+    pub valid: usize,
+    pub equilateral: usize,
+    pub isosceles: usize,
+    pub scalene: usize,
+    pub acute: usize,
+    pub right: usize,
+    pub obtuse: usize,
+}
+
+impl Report {
+    pub fn new(shapes: &[[u64; 3]]) -> Self {
+        let mut report = Report {
+            total: shapes.len(),
+            ..Default::default()
+        };
+        for &sides in shapes {
+            if let Some((side_class, angle_class)) = classify(sides) {
+                report.valid += 1;
+                match side_class {
+                    SideClass::Equilateral => report.equilateral += 1,
+                    SideClass::Isosceles => report.isosceles += 1,
+                    SideClass::Scalene => report.scalene += 1,
+                }
+                match angle_class {
+                    AngleClass::Acute => report.acute += 1,
+                    AngleClass::Right => report.right += 1,
+                    AngleClass::Obtuse => report.obtuse += 1,
+                }
+            }
+        }
+        report
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} of {} valid", self.valid, self.total)?;
+        writeln!(
+            f,
+            "equilateral: {}, isosceles: {}, scalene: {}",
+            self.equilateral, self.isosceles, self.scalene
+        )?;
+        write!(
+            f,
+            "acute: {}, right: {}, obtuse: {}",
+            self.acute, self.right, self.obtuse
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid() {
+        assert!(is_valid(&[3, 4, 5]));
+        assert!(!is_valid(&[5, 10, 25]));
+        assert!(!is_valid(&[1, 2, 3]));
+        assert!(is_valid(&[1, 1, 1, 2]));
+        assert!(!is_valid(&[1, 1, 1, 3]));
+        assert!(is_valid(&[2, 2, 2, 2, 2]));
+        assert!(!is_valid(&[1, 1]));
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            Some((SideClass::Scalene, AngleClass::Right)),
+            classify([5, 3, 4])
+        );
+        assert_eq!(
+            Some((SideClass::Equilateral, AngleClass::Acute)),
+            classify([7, 7, 7])
+        );
+        assert_eq!(
+            Some((SideClass::Isosceles, AngleClass::Obtuse)),
+            classify([2, 2, 3])
+        );
+        assert_eq!(None, classify([5, 10, 25]));
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(&[[3, 4, 5], [5, 10, 25], [2, 2, 3], [7, 7, 7]]);
+        assert_eq!(4, report.total);
+        assert_eq!(3, report.valid);
+        assert_eq!(
+            (1, 1, 1),
+            (report.equilateral, report.isosceles, report.scalene)
+        );
+        assert_eq!((1, 1, 1), (report.acute, report.right, report.obtuse));
+    }
+
+    #[test]
+    fn test_near_overflow() {
+        let max = u64::MAX;
+        assert!(is_valid(&[max, max, max]));
+        assert!(is_valid(&[max, max - 1, 2]));
+        assert!(!is_valid(&[max, max - 1, 1]));
+        assert!(is_valid(&[max; 8]));
+        assert_eq!(
+            Some((SideClass::Equilateral, AngleClass::Acute)),
+            classify([max, max, max])
+        );
+        assert_eq!(
+            Some((SideClass::Isosceles, AngleClass::Acute)),
+            classify([max, max, max - 1])
+        );
+        assert_eq!(
+            Some((SideClass::Isosceles, AngleClass::Obtuse)),
+            classify([max, max / 2 + 1, max / 2 + 1])
+        );
+        assert_eq!(None, classify([max, max / 2, max / 2 + 1]));
+        let leg = 3 * (max / 5);
+        assert_eq!(
+            Some((SideClass::Scalene, AngleClass::Right)),
+            classify([leg, 4 * (max / 5), 5 * (max / 5)])
+        );
+    }
+}
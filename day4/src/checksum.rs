@@ -0,0 +1,134 @@
+pub trait ChecksumPolicy {
+    fn calculate(&self, name: &str) -> Vec<char>;
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum TieBreak {
+    Alphabetical,
+    ReverseAlphabetical,
+    FirstAppearance,
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Order {
+    MostCommon,
+    LeastCommon,
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Frequency {
+    pub count: usize,
+    pub order: Order,
+    pub tie: TieBreak,
+    pub digits: bool,
+}
+
+impl Default for Frequency {
+    fn default() -> Self {
+        Frequency {
+            count: 5,
+            order: Order::MostCommon,
+            tie: TieBreak::Alphabetical,
+            digits: false,
+        }
+    }
+}
+
+fn slot(c: char, digits: bool) -> Option<usize> {
+    if c.is_ascii_lowercase() {
+        Some(c as usize - 'a' as usize + 10)
+    } else if digits && c.is_ascii_digit() {
+        Some(c as usize - '0' as usize)
+    } else {
+        None
+    }
+}
+
+fn symbol(slot: usize) -> char {
+    if slot < 10 {
+        (b'0' + slot as u8) as char
+    } else {
+        (b'a' + (slot - 10) as u8) as char
+    }
+}
+
+impl ChecksumPolicy for Frequency {
+    fn calculate(&self, name: &str) -> Vec<char> {
+        let mut counts = [0usize; 36];
+        let mut first = [usize::MAX; 36];
+        for (idx, c) in name.chars().enumerate() {
+            if let Some(slot) = slot(c, self.digits) {
+                counts[slot] += 1;
+                first[slot] = first[slot].min(idx);
+            }
+        }
+        let mut seen = (0..36).filter(|&s| counts[s] > 0).collect::<Vec<usize>>();
+        seen.sort_by_key(|&s| {
+            let count = match self.order {
+                Order::MostCommon => usize::MAX - counts[s],
+                Order::LeastCommon => counts[s],
+            };
+            let tie = match self.tie {
+                TieBreak::Alphabetical => s,
+                TieBreak::ReverseAlphabetical => 36 - s,
+                TieBreak::FirstAppearance => first[s],
+            };
+            (count, tie)
+        });
+        seen.into_iter().take(self.count).map(symbol).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calculate(policy: Frequency, name: &str) -> String {
+        policy.calculate(name).into_iter().collect()
+    }
+
+    #[test]
+    fn test_default() {
+        let policy = Frequency::default();
+        assert_eq!("abxyz", calculate(policy, "aaaaa-bbb-z-y-x"));
+        assert_eq!("abcde", calculate(policy, "a-b-c-d-e-f-g-h"));
+        assert_eq!("oarel", calculate(policy, "not-a-real-room"));
+        assert_eq!("abc", calculate(policy, "aabbc"));
+    }
+
+    #[test]
+    fn test_tie_breaks() {
+        let policy = Frequency {
+            tie: TieBreak::ReverseAlphabetical,
+            ..Default::default()
+        };
+        assert_eq!("abzyx", calculate(policy, "aaaaa-bbb-z-y-x"));
+        let policy = Frequency {
+            tie: TieBreak::FirstAppearance,
+            ..Default::default()
+        };
+        assert_eq!("abzyx", calculate(policy, "aaaaa-bbb-z-y-x"));
+        assert_eq!("hgfed", calculate(policy, "h-g-f-e-d-c-b-a"));
+    }
+
+    #[test]
+    fn test_least_common() {
+        let policy = Frequency {
+            count: 3,
+            order: Order::LeastCommon,
+            ..Default::default()
+        };
+        assert_eq!("xyz", calculate(policy, "aaaaa-bbb-z-y-x"));
+        assert_eq!("cba", calculate(policy, "aaabbc"));
+    }
+
+    #[test]
+    fn test_digits() {
+        let policy = Frequency {
+            digits: true,
+            ..Default::default()
+        };
+        assert_eq!("1ab9", calculate(policy, "a1-1b-11-a9-b"));
+        assert_eq!("ab", calculate(Frequency::default(), "a1-1b-11-a9-b"));
+    }
+}
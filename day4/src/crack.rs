@@ -0,0 +1,165 @@
+use std::cmp::Ordering;
+
+use super::Room;
+
+const FREQUENCIES: [f64; 26] = [
+    8.2, 1.5, 2.8, 4.3, 12.7, 2.2, 2.0, 6.1, 7.0, 0.15, 0.77, 4.0, 2.4, 6.7, 7.5, 1.9, 0.095, 6.0,
+    6.3, 9.1, 2.8, 0.98, 2.4, 0.15, 2.0, 0.074,
+];
+
+const DICTIONARY: [&str; 48] = [
+    "north",
+    "pole",
+    "northpole",
+    "object",
+    "storage",
+    "candy",
+    "chocolate",
+    "egg",
+    "bunny",
+    "rabbit",
+    "scavenger",
+    "hunt",
+    "research",
+    "department",
+    "projects",
+    "laboratory",
+    "design",
+    "weaponry",
+    "cryogenic",
+    "radiator",
+    "classified",
+    "biohazard",
+    "dye",
+    "flower",
+    "basket",
+    "jellybean",
+    "grass",
+    "plastic",
+    "fuzzy",
+    "magnetic",
+    "military",
+    "grade",
+    "entertainment",
+    "engineering",
+    "reindeer",
+    "trap",
+    "workshop",
+    "unstable",
+    "corrosive",
+    "sales",
+    "training",
+    "technology",
+    "deployment",
+    "center",
+    "colorful",
+    "decorations",
+    "containment",
+    "operations",
+];
+
+const WORD_BONUS: f64 = 5.0;
+
+pub fn shift(name: &str, by: usize) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_lowercase() {
+                (((c as usize - 'a' as usize + by) % 26) + 'a' as usize) as u8 as char
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+pub fn score(text: &str) -> f64 {
+    let mut total = 0.0;
+    let mut letters = 0;
+    for c in text.chars().filter(char::is_ascii_lowercase) {
+        total += FREQUENCIES[c as usize - 'a' as usize].ln();
+        letters += 1;
+    }
+    if letters > 0 {
+        total /= letters as f64;
+    }
+    for word in text.split(' ') {
+        if DICTIONARY.contains(&word) {
+            total += WORD_BONUS;
+        }
+    }
+    total
+}
+
+pub fn candidates(name: &str) -> Vec<(usize, String, f64)> {
+    let mut retval = (0..26)
+        .map(|by| {
+            let text = shift(name, by);
+            let score = score(&text);
+            (by, text, score)
+        })
+        .collect::<Vec<(usize, String, f64)>>();
+    retval.sort_by(|(_, _, s1), (_, _, s2)| s2.partial_cmp(s1).unwrap_or(Ordering::Equal));
+    retval
+}
+
+pub fn crack(name: &str) -> (usize, String) {
+    let (by, text, _) = candidates(name).swap_remove(0);
+    (by, text)
+}
+
+pub fn accuracy(input: &str) -> (usize, usize) {
+    let mut matched = 0;
+    let mut total = 0;
+    for line in input.lines() {
+        let room = Room::new(line);
+        if room.real() {
+            let name = room.name.iter().collect::<String>();
+            let (by, _) = crack(&name);
+            if by == room.sector_id % 26 {
+                matched += 1;
+            }
+            total += 1;
+        }
+    }
+    (matched, total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crack() {
+        assert_eq!(
+            (343 % 26, "very encrypted name".to_string()),
+            crack("qzmt zixmtkozy ivhz")
+        );
+        let line = Room::encrypt("northpole object storage", 501).unwrap();
+        let room = Room::new(&line);
+        let name = room.name.iter().collect::<String>();
+        assert_eq!(
+            (501 % 26, "northpole object storage".to_string()),
+            crack(&name)
+        );
+    }
+
+    #[test]
+    fn test_candidates() {
+        let ranked = candidates("qzmt zixmtkozy ivhz");
+        assert_eq!(26, ranked.len());
+        assert_eq!("very encrypted name", ranked[0].1);
+        assert!(ranked.windows(2).all(|w| w[0].2 >= w[1].2));
+    }
+
+    #[test]
+    fn test_accuracy() {
+        let input = [
+            Room::encrypt("northpole object storage", 26).unwrap(),
+            Room::encrypt_decoy("north pole decoy room", 52).unwrap(),
+            Room::encrypt("scavenger hunt research", 404).unwrap(),
+            Room::encrypt("magnetic jellybean engineering", 311).unwrap(),
+        ]
+        .join("\n");
+        assert_eq!((3, 3), accuracy(&input));
+    }
+}
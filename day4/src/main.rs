@@ -1,6 +1,12 @@
-use std::collections::HashMap;
+mod checksum;
+mod crack;
+
+use std::env;
 use std::fs;
 
+use checksum::{ChecksumPolicy, Frequency, Order, TieBreak};
+use regex::Regex;
+
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 struct Room {
     name: Vec<char>,
@@ -11,28 +17,19 @@ struct Room {
 
 impl Room {
     fn new(input: &str) -> Self {
+        Room::with_policy(input, &Frequency::default())
+    }
+
+    fn with_policy(input: &str, policy: &dyn ChecksumPolicy) -> Self {
         let temp = input.replace("]", "");
         let mut splits = temp.split("[");
-        let mut name = Vec::new();
-        let mut letters = HashMap::new();
-        let mut sector_id = String::new();
-        for c in splits.next().unwrap().chars() {
-            if c.is_ascii_lowercase() {
-                name.push(c);
-                if let Some(val) = letters.get(&c) {
-                    letters.insert(c, val + 1);
-                } else {
-                    letters.insert(c, 1);
-                }
-            } else if c.is_numeric() {
-                sector_id.push(c);
-            } else if c == '-' {
-                name.push(' ');
-            }
-        }
-        name.pop();
+        let (encrypted, sector_id) = splits.next().unwrap().rsplit_once('-').unwrap();
+        let name = encrypted
+            .chars()
+            .map(|c| if c == '-' { ' ' } else { c })
+            .collect();
         let sector_id = sector_id.parse().unwrap();
-        let top = Room::calculate_top(&letters);
+        let top = policy.calculate(encrypted);
         let checksum = splits.next().unwrap().chars().collect();
         Room {
             name,
@@ -42,24 +39,14 @@ impl Room {
         }
     }
 
-    fn calculate_top(letters: &HashMap<char, usize>) -> Vec<char> {
-        let mut retval = letters
-            .iter()
-            .map(|(k, v)| (*k, *v))
-            .collect::<Vec<(char, usize)>>();
-        retval.sort_by(|(k1, _), (k2, _)| k1.cmp(k2));
-        retval.sort_by(|(_, v1), (_, v2)| v2.cmp(v1));
-        retval.iter().map(|(k, _)| *k).collect()
-    }
-
     fn real(&self) -> bool {
-        self.top[..5] == self.checksum[..5]
+        self.top == self.checksum
     }
 
     fn decrypt(&self) -> String {
         let mut retval = String::new();
         for c in &self.name {
-            if *c == ' ' {
+            if !c.is_ascii_lowercase() {
                 retval.push(*c);
             } else {
                 retval.push(
@@ -70,12 +57,52 @@ impl Room {
         }
         retval
     }
+
+    fn encrypt(name: &str, sector_id: usize) -> Option<String> {
+        let mut retval = String::new();
+        for c in name.chars() {
+            if c.is_ascii_lowercase() {
+                let shifted = (((c as usize - 'a' as usize + 26 - sector_id % 26) % 26)
+                    + 'a' as usize) as u8 as char;
+                retval.push(shifted);
+            } else if c == ' ' {
+                retval.push('-');
+            } else {
+                return None;
+            }
+        }
+        let top = Frequency::default().calculate(&retval);
+        if top.len() < 5 {
+            return None;
+        }
+        let checksum = top.iter().collect::<String>();
+        Some(format!("{}-{}[{}]", retval, sector_id, checksum))
+    }
+
+    fn encrypt_decoy(name: &str, sector_id: usize) -> Option<String> {
+        let line = Room::encrypt(name, sector_id)?;
+        let (prefix, checksum) = line.split_at(line.len() - 6);
+        let mut checksum = checksum[..5].chars().collect::<Vec<char>>();
+        let mut last = checksum.pop().unwrap();
+        loop {
+            last = (((last as u8 - b'a' + 1) % 26) + b'a') as char;
+            if !checksum.contains(&last) {
+                break;
+            }
+        }
+        checksum.push(last);
+        Some(format!(
+            "{}{}]",
+            prefix,
+            checksum.iter().collect::<String>()
+        ))
+    }
 }
 
-fn part1(input: &str) -> usize {
+fn sum_real(input: &str, policy: &dyn ChecksumPolicy) -> usize {
     let mut sum = 0;
     for line in input.lines() {
-        let room = Room::new(line);
+        let room = Room::with_policy(line, policy);
         if room.real() {
             sum += room.sector_id;
         }
@@ -83,25 +110,107 @@ fn part1(input: &str) -> usize {
     sum
 }
 
-fn part2(input: &str) -> usize {
-    let mut rooms = Vec::new();
+fn part1(input: &str) -> usize {
+    sum_real(input, &Frequency::default())
+}
+
+#[derive(Clone, Debug)]
+enum Query {
+    Keywords(Vec<String>),
+    Pattern(Regex),
+}
+
+impl Query {
+    fn north() -> Self {
+        Query::Keywords(vec!["north".to_string()])
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Query::Keywords(keywords) => keywords.iter().any(|k| name.contains(k.as_str())),
+            Query::Pattern(regex) => regex.is_match(name),
+        }
+    }
+}
+
+fn search(input: &str, query: &Query) -> Vec<(usize, String)> {
+    let mut retval = Vec::new();
     for line in input.lines() {
         let room = Room::new(line);
         if room.real() {
-            rooms.push(room);
+            let name = room.decrypt();
+            if query.matches(&name) {
+                retval.push((room.sector_id, name));
+            }
         }
     }
-    for room in rooms {
-        let code = room.decrypt();
-        if code.contains("north") {
-            return room.sector_id;
-        }
+    retval
+}
+
+fn part2(input: &str) -> usize {
+    match search(input, &Query::north()).first() {
+        Some((sector_id, _)) => *sector_id,
+        None => 0,
     }
-    0
 }
 
 fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let query = match args.first().map(String::as_str) {
+        Some("encrypt") if args.len() > 2 => {
+            let sector_id = args[1].parse().expect("sector id should be a number");
+            let name = args[2..].join(" ");
+            match (
+                Room::encrypt(&name, sector_id),
+                Room::encrypt_decoy(&name, sector_id),
+            ) {
+                (Some(real), Some(decoy)) => {
+                    println!("{}", real);
+                    println!("{}", decoy);
+                }
+                _ => println!("{} cannot be encrypted", name),
+            }
+            return;
+        }
+        Some("search") if args.len() > 1 => Some(Query::Keywords(args[1..].to_vec())),
+        Some("regex") if args.len() == 2 => Some(Query::Pattern(
+            Regex::new(&args[1]).expect("regex should be valid"),
+        )),
+        _ => None,
+    };
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
+    if args.first().map(String::as_str) == Some("checksum") {
+        let mut policy = Frequency::default();
+        for arg in &args[1..] {
+            match arg.as_str() {
+                "least" => policy.order = Order::LeastCommon,
+                "reverse" => policy.tie = TieBreak::ReverseAlphabetical,
+                "first" => policy.tie = TieBreak::FirstAppearance,
+                "digits" => policy.digits = true,
+                _ => policy.count = arg.parse().expect("count should be a number"),
+            }
+        }
+        println!("{}", sum_real(&input, &policy));
+        return;
+    }
+    if args.first().map(String::as_str) == Some("crack") {
+        for line in input.lines() {
+            let room = Room::new(line);
+            if room.real() {
+                let (by, text) = crack::crack(&room.name.iter().collect::<String>());
+                println!("{} {} {}", room.sector_id, by, text);
+            }
+        }
+        let (matched, total) = crack::accuracy(&input);
+        println!("{} of {} cracked with the sector id shift", matched, total);
+        return;
+    }
+    if let Some(query) = query {
+        for (sector_id, name) in search(&input, &query) {
+            println!("{} {}", sector_id, name);
+        }
+        return;
+    }
     println!("{}", part1(&input));
     println!("{}", part2(&input));
 }
@@ -121,4 +230,103 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!(0, part2(&input));
     }
+
+    #[test]
+    fn test_encrypt() {
+        let line = Room::encrypt("very encrypted name", 343).unwrap();
+        assert_eq!("qzmt-zixmtkozy-ivhz-343[zimth]", line);
+        let room = Room::new(&line);
+        assert!(room.real());
+        assert_eq!(343, room.sector_id);
+        assert_eq!("very encrypted name", room.decrypt());
+
+        let decoy = Room::encrypt_decoy("very encrypted name", 343).unwrap();
+        assert_eq!("qzmt-zixmtkozy-ivhz-343[zimtj]", decoy);
+        let room = Room::new(&decoy);
+        assert!(!room.real());
+        assert_eq!("very encrypted name", room.decrypt());
+    }
+
+    #[test]
+    fn test_encrypt_invalid() {
+        assert_eq!(None, Room::encrypt("abcd", 1));
+        assert_eq!(None, Room::encrypt("north pole 1", 1));
+        assert_eq!(None, Room::encrypt_decoy("aaaa bbbb", 1));
+    }
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        for sector_id in [0, 1, 25, 26, 27, 999] {
+            for name in ["northpole object storage", "the quick brown fox", "zyxwv"] {
+                let room = Room::new(&Room::encrypt(name, sector_id).unwrap());
+                assert!(room.real());
+                assert_eq!(name, room.decrypt());
+                let room = Room::new(&Room::encrypt_decoy(name, sector_id).unwrap());
+                assert!(!room.real());
+                assert_eq!(name, room.decrypt());
+            }
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let input = [
+            Room::encrypt("northpole object storage", 26).unwrap(),
+            Room::encrypt_decoy("north pole decoy room", 52).unwrap(),
+            Room::encrypt("scavenger hunt research", 404).unwrap(),
+            Room::encrypt("north pole candy storage", 700).unwrap(),
+        ]
+        .join("\n");
+        assert_eq!(26, part2(&input));
+        assert_eq!(
+            vec![
+                (26, "northpole object storage".to_string()),
+                (700, "north pole candy storage".to_string()),
+            ],
+            search(&input, &Query::north())
+        );
+        assert_eq!(
+            vec![(404, "scavenger hunt research".to_string())],
+            search(
+                &input,
+                &Query::Keywords(vec!["hunt".to_string(), "bunny".to_string()])
+            )
+        );
+        assert_eq!(
+            vec![
+                (26, "northpole object storage".to_string()),
+                (700, "north pole candy storage".to_string()),
+            ],
+            search(&input, &Query::Pattern(Regex::new(" storage$").unwrap()))
+        );
+        assert!(search(&input, &Query::Pattern(Regex::new("^decoy").unwrap())).is_empty());
+        assert_eq!(0, part2(input.lines().nth(2).unwrap()));
+    }
+
+    #[test]
+    fn test_policies() {
+        let input = "aaaaa-bbb-z-y-x-123[abxyz]\na-b-c-d-e-f-g-h-987[abcde]\nnot-a-real-room-404[oarel]\ntotally-real-room-200[decoy]";
+        assert_eq!(1514, part1(input));
+        let reverse = Frequency {
+            tie: TieBreak::ReverseAlphabetical,
+            ..Default::default()
+        };
+        assert_eq!(0, sum_real(input, &reverse));
+        assert_eq!(123, sum_real("aaaaa-bbb-z-y-x-123[abzyx]", &reverse));
+        let least = Frequency {
+            count: 3,
+            order: Order::LeastCommon,
+            ..Default::default()
+        };
+        assert_eq!(123, sum_real("aaaaa-bbb-z-y-x-123[xyz]", &least));
+        let digits = Frequency {
+            digits: true,
+            ..Default::default()
+        };
+        let room = Room::with_policy("r2d2-c3po-77[23cdo]", &digits);
+        assert!(room.real());
+        assert_eq!(77, room.sector_id);
+        assert_eq!("q2c2 b3on", room.decrypt());
+        assert!(!Room::new("r2d2-c3po-77[23cdo]").real());
+    }
 }
@@ -1,66 +1,223 @@
+use std::env;
 use std::fs;
+use std::io::{self, Write};
 
-use md5;
-use rayon::prelude::*;
-
-fn compute(input: &str, num: usize) -> char {
-    let test = input.to_string() + num.to_string().as_str();
-    let digest = md5::compute(test);
-    let hash = format!("{:x}", digest);
-    if hash.len() >= 6 {
-        let chars = hash.chars().collect::<Vec<char>>();
-        if chars[..5].iter().all(|c| *c == '0') {
-            return chars[5];
+use miner::{Algorithm, Difficulty, Hit, Miner};
+
+const LIMIT: usize = 99999999;
+
+const FRAME: usize = 100000;
+
+const CHECKPOINT: usize = 10;
+
+const STATE: &str = "day5.state";
+
+fn hex_char(nibble: u8) -> char {
+    char::from_digit(nibble as u32, 16).unwrap()
+}
+
+fn compute(hit: &Hit) -> char {
+    hex_char(hit.nibble(5))
+}
+
+fn compute_with_position(hit: &Hit) -> Option<(char, usize)> {
+    let loc = hit.nibble(5) as usize;
+    if loc < 8 {
+        Some((hex_char(hit.nibble(6)), loc))
+    } else {
+        None
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+struct Progress {
+    next: usize,
+    slots: [char; 8],
+}
+
+impl Progress {
+    fn new() -> Self {
+        Progress {
+            next: 0,
+            slots: [' '; 8],
         }
     }
-    ' '
+
+    fn filled(&self) -> usize {
+        self.slots.iter().filter(|c| **c != ' ').count()
+    }
 }
 
-fn compute_with_position(input: &str, num: usize) -> (char, usize) {
-    let test = input.to_string() + num.to_string().as_str();
-    let digest = md5::compute(test);
-    let hash = format!("{:x}", digest);
-    if hash.len() >= 6 {
-        let chars = hash.chars().collect::<Vec<char>>();
-        if chars[..5].iter().all(|c| *c == '0') {
-            if let Ok(loc) = chars[5].to_string().parse() {
-                if loc < 8 {
-                    return (chars[6], loc);
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+struct Checkpoint {
+    door: String,
+    parts: [Progress; 2],
+}
+
+impl Checkpoint {
+    fn new(door: &str) -> Self {
+        Checkpoint {
+            door: door.to_string(),
+            parts: [Progress::new(); 2],
+        }
+    }
+
+    fn load(path: &str, door: &str) -> Option<Self> {
+        let state = fs::read_to_string(path).ok()?;
+        let mut lines = state.lines();
+        if lines.next()? != door.trim() {
+            return None;
+        }
+        let mut checkpoint = Checkpoint::new(door);
+        for progress in &mut checkpoint.parts {
+            let mut splits = lines.next()?.split(' ');
+            progress.next = splits.next()?.parse().ok()?;
+            let slots = splits
+                .next()?
+                .chars()
+                .map(|c| if c == '_' { ' ' } else { c })
+                .collect::<Vec<char>>();
+            progress.slots = slots.try_into().ok()?;
+        }
+        Some(checkpoint)
+    }
+
+    fn save(&self, path: &str) -> io::Result<()> {
+        let mut state = format!("{}\n", self.door.trim());
+        for progress in &self.parts {
+            let slots = progress
+                .slots
+                .iter()
+                .map(|c| if *c == ' ' { '_' } else { *c })
+                .collect::<String>();
+            state.push_str(&format!("{} {}\n", progress.next, slots));
+        }
+        fs::write(path, state)
+    }
+}
+
+fn decrypt<F>(input: &str, positional: bool, progress: &mut Progress, mut frame: F) -> String
+where
+    F: FnMut(&Progress),
+{
+    let mut filled = progress.filled();
+    let mut framed = false;
+    while progress.next < LIMIT && filled < 8 {
+        let stop = (progress.next + FRAME).min(LIMIT);
+        for hit in Miner::new(
+            input,
+            Algorithm::Md5,
+            Difficulty::Nibbles(5),
+            progress.next..stop,
+        ) {
+            let slot = if positional {
+                compute_with_position(&hit)
+            } else {
+                Some((compute(&hit), filled))
+            };
+            if let Some((c, loc)) = slot {
+                if progress.slots[loc] == ' ' {
+                    progress.slots[loc] = c;
+                    filled += 1;
                 }
             }
+            if filled == 8 {
+                break;
+            }
         }
+        progress.next = stop;
+        frame(progress);
+        framed = true;
+    }
+    if !framed {
+        frame(progress);
     }
-    (' ', 0)
+    progress.slots.iter().collect()
 }
 
-fn part1(input: &str) -> String {
-    let retval = (0..99999999)
-        .into_par_iter()
-        .map(|x| compute(input, x))
-        .filter(|c| c.is_alphanumeric())
-        .collect::<Vec<char>>();
-    retval[..8].into_iter().collect()
+fn part1<F: FnMut(&Progress)>(input: &str, progress: &mut Progress, frame: F) -> String {
+    decrypt(input, false, progress, frame)
 }
 
-fn part2(input: &str) -> String {
-    let temp = (0..99999999)
-        .into_par_iter()
-        .map(|x| compute_with_position(input, x))
-        .filter(|(c, _)| c.is_alphanumeric())
-        .collect::<Vec<(char, usize)>>();
-    let mut retval = vec![' ',' ',' ',' ',' ',' ',' ',' '];
-    for (c, loc) in temp {
-        if retval[loc] == ' ' {
-            retval[loc] = c;
+fn part2<F: FnMut(&Progress)>(input: &str, progress: &mut Progress, frame: F) -> String {
+    decrypt(input, true, progress, frame)
+}
+
+fn churn(seed: &mut u64) -> char {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 7;
+    *seed ^= *seed << 17;
+    hex_char((*seed & 0x0f) as u8)
+}
+
+fn render(slots: &[char; 8], seed: &mut u64) -> String {
+    let mut retval = String::new();
+    for c in slots {
+        if *c == ' ' {
+            retval.push_str(&format!("\x1b[2m{}\x1b[0m", churn(seed)));
+        } else {
+            retval.push_str(&format!("\x1b[1;32m{}\x1b[0m", c));
+        }
+    }
+    retval
+}
+
+fn save(checkpoint: &Checkpoint, warned: &mut bool) {
+    if let Err(err) = checkpoint.save(STATE) {
+        if !*warned {
+            eprintln!("warning: could not save {}: {}", STATE, err);
+            *warned = true;
         }
     }
-    retval.into_iter().collect()
 }
 
 fn main() {
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
-    println!("{}", part1(&input));
-    println!("{}", part2(&input));
+    let args = env::args().collect::<Vec<String>>();
+    let cinematic = args.iter().any(|arg| arg == "--cinematic");
+    let mut checkpoint = Checkpoint::new(&input);
+    if args.iter().any(|arg| arg == "--resume") {
+        if let Some(saved) = Checkpoint::load(STATE, &input) {
+            checkpoint = saved;
+        }
+    }
+    let mut seed = 0x2016_0005;
+    let mut stdout = io::stdout();
+    let mut warned = false;
+    for part in 0..2 {
+        let mut progress = checkpoint.parts[part];
+        let mut frames = 0;
+        let frame = |progress: &Progress| {
+            frames += 1;
+            if frames % CHECKPOINT == 0 {
+                let mut saved = checkpoint.clone();
+                saved.parts[part] = *progress;
+                save(&saved, &mut warned);
+            }
+            if cinematic {
+                print!("\r{}", render(&progress.slots, &mut seed));
+                stdout.flush().unwrap();
+            }
+        };
+        let password = if part == 0 {
+            part1(&input, &mut progress, frame)
+        } else {
+            part2(&input, &mut progress, frame)
+        };
+        checkpoint.parts[part] = progress;
+        save(&checkpoint, &mut warned);
+        if cinematic {
+            println!();
+        } else {
+            println!("{}", password);
+        }
+    }
+    match fs::remove_file(STATE) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            eprintln!("warning: could not remove {}: {}", STATE, err)
+        }
+        _ => {}
+    }
 }
 
 #[cfg(test)]
@@ -70,12 +227,115 @@ mod tests {
     #[test]
     fn test_p1() {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
-        assert_eq!("18f47a30".to_string(), part1(&input));
+        assert_eq!(
+            "18f47a30".to_string(),
+            part1(&input, &mut Progress::new(), |_| {})
+        );
     }
 
     #[test]
     fn test_p2() {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
-        assert_eq!("05ace8e3".to_string(), part2(&input));
+        assert_eq!(
+            "05ace8e3".to_string(),
+            part2(&input, &mut Progress::new(), |_| {})
+        );
+    }
+
+    #[test]
+    fn test_compute() {
+        let hit = Miner::new(
+            "abc",
+            Algorithm::Md5,
+            Difficulty::Nibbles(5),
+            3231929..LIMIT,
+        )
+        .next()
+        .unwrap();
+        assert_eq!(3231929, hit.index);
+        assert_eq!('1', compute(&hit));
+        assert_eq!(Some(('5', 1)), compute_with_position(&hit));
+        let hit = Hit {
+            index: 0,
+            digest: vec![0, 0, 0x08, 0xf0],
+        };
+        assert_eq!('8', compute(&hit));
+        assert_eq!(None, compute_with_position(&hit));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut seed = 1;
+        let frame = render(&['1', ' ', '3', ' ', ' ', ' ', ' ', '8'], &mut seed);
+        assert!(frame.starts_with("\x1b[1;32m1\x1b[0m\x1b[2m"));
+        assert!(frame.ends_with("\x1b[1;32m8\x1b[0m"));
+        assert_eq!(8, frame.matches("\x1b[0m").count());
+        assert_eq!(5, frame.matches("\x1b[2m").count());
+        assert_ne!(
+            frame,
+            render(&['1', ' ', '3', ' ', ' ', ' ', ' ', '8'], &mut seed)
+        );
+        assert_eq!("\x1b[1;32m0\x1b[0m".repeat(8), render(&['0'; 8], &mut seed));
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let path = env::temp_dir().join(format!("day5-{}.state", std::process::id()));
+        let path = path.to_str().unwrap();
+        let mut checkpoint = Checkpoint::new("abc\n");
+        checkpoint.parts[0] = Progress {
+            next: 5300000,
+            slots: ['1', '8', 'f', ' ', ' ', ' ', ' ', ' '],
+        };
+        checkpoint.parts[1].next = 100000;
+        checkpoint.save(path).unwrap();
+        assert_eq!(
+            "abc\n5300000 18f_____\n100000 ________\n",
+            fs::read_to_string(path).unwrap()
+        );
+        assert_eq!(Some(checkpoint), Checkpoint::load(path, "abc\n"));
+        assert_eq!(None, Checkpoint::load(path, "abd"));
+        fs::write(path, "abc\n5300000 18f\n").unwrap();
+        assert_eq!(None, Checkpoint::load(path, "abc"));
+        fs::remove_file(path).unwrap();
+        assert_eq!(None, Checkpoint::load(path, "abc"));
+    }
+
+    #[test]
+    fn test_resume() {
+        let mut progress = Progress {
+            next: 3200000,
+            slots: ['a', 'b', 'c', 'd', 'e', 'f', '0', ' '],
+        };
+        let mut frames = Vec::new();
+        let password = decrypt("abc", false, &mut progress, |p| frames.push(*p));
+        assert_eq!("abcdef01", password);
+        assert_eq!(3300000, progress.next);
+        assert_eq!(vec![progress], frames);
+
+        let mut progress = Progress {
+            next: 3200000,
+            slots: ['a', ' ', 'c', 'd', 'e', 'f', '0', '1'],
+        };
+        assert_eq!("a5cdef01", decrypt("abc", true, &mut progress, |_| {}));
+
+        let mut progress = Progress {
+            next: 0,
+            slots: ['1', '8', 'f', '4', '7', 'a', '3', '0'],
+        };
+        let mut frames = Vec::new();
+        let password = decrypt("abc", false, &mut progress, |p| frames.push(*p));
+        assert_eq!("18f47a30", password);
+        assert_eq!(0, progress.next);
+        assert_eq!(vec![progress], frames);
+
+        let mut progress = Progress {
+            next: 99999999,
+            slots: ['0', '5', 'a', 'c', 'e', '8', 'e', '3'],
+        };
+        let mut frames = Vec::new();
+        let password = part2("abc", &mut progress, |p| frames.push(*p));
+        assert_eq!("05ace8e3", password);
+        assert_eq!(vec![progress], frames);
     }
 }
@@ -1,60 +1,169 @@
 use std::collections::HashMap;
-use std::fs;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
 
-fn build_cols(input: &str) -> Vec<HashMap<char, usize>> {
-    let num_cols = input.lines().next().unwrap().len();
-    let mut columns = Vec::new();
-    for _ in 0..num_cols {
-        columns.push(HashMap::new())
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+enum Order {
+    MostCommon,
+    LeastCommon,
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+enum TieBreak {
+    Alphabetical,
+    ReverseAlphabetical,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Decoded {
+    message: String,
+    columns: Vec<Vec<(char, usize)>>,
+    margins: Vec<Option<usize>>,
+    samples: Vec<usize>,
+}
+
+impl Decoded {
+    fn ambiguous(&self) -> Vec<usize> {
+        self.margins
+            .iter()
+            .enumerate()
+            .filter(|(_, margin)| **margin == Some(0))
+            .map(|(idx, _)| idx)
+            .collect()
     }
-    for line in input.lines() {
+}
+
+fn rank(column: &HashMap<char, usize>, order: Order, tie: TieBreak) -> Vec<(char, usize)> {
+    let mut retval = column
+        .iter()
+        .map(|(k, v)| (*k, *v))
+        .collect::<Vec<(char, usize)>>();
+    retval.sort_by(|(k1, v1), (k2, v2)| {
+        let by_count = match order {
+            Order::MostCommon => v2.cmp(v1),
+            Order::LeastCommon => v1.cmp(v2),
+        };
+        let by_key = match tie {
+            TieBreak::Alphabetical => k1.cmp(k2),
+            TieBreak::ReverseAlphabetical => k2.cmp(k1),
+        };
+        by_count.then(by_key)
+    });
+    retval
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+struct Decoder {
+    columns: Vec<HashMap<char, usize>>,
+    samples: Vec<usize>,
+    lines: usize,
+}
+
+impl Decoder {
+    fn push(&mut self, line: &str) {
         for (idx, c) in line.chars().enumerate() {
-            if let Some(&count) = columns[idx].get(&c) {
-                columns[idx].insert(c, count+1);
-            } else {
-                columns[idx].insert(c, 1);
+            if idx == self.columns.len() {
+                self.columns.push(HashMap::new());
+                self.samples.push(0);
             }
+            *self.columns[idx].entry(c).or_insert(0) += 1;
+            self.samples[idx] += 1;
         }
+        self.lines += 1;
     }
-    columns
-}
 
-fn part1(input: &str) -> String {
-    let mut result = String::new();
-    let columns = build_cols(input);
-    for column in columns {
-        let mut max = (' ', 0);
-        for (key, value) in column {
-            if value > max.1 {
-                max = (key, value);
-            }
+    fn decode(&self, order: Order, tie: TieBreak) -> Decoded {
+        let mut decoded = Decoded {
+            samples: self.samples.clone(),
+            ..Default::default()
+        };
+        for column in &self.columns {
+            let ranked = rank(column, order, tie);
+            decoded.message.push(ranked[0].0);
+            decoded
+                .margins
+                .push(ranked.get(1).map(|second| ranked[0].1.abs_diff(second.1)));
+            decoded.columns.push(ranked);
         }
-        result.push(max.0);
-
+        decoded
     }
-    result
 }
 
-fn part2(input: &str) -> String {
-    let mut result = String::new();
-    let columns = build_cols(input);
-    for column in columns {
-        let mut min = (' ', usize::MAX);
-        for (key, value) in column {
-            if value < min.1 {
-                min = (key, value);
-            }
+fn stream<R, F>(reader: R, every: usize, mut report: F) -> io::Result<Decoder>
+where
+    R: BufRead,
+    F: FnMut(&Decoder),
+{
+    let mut decoder = Decoder::default();
+    for line in reader.lines() {
+        decoder.push(&line?);
+        if every > 0 && decoder.lines % every == 0 {
+            report(&decoder);
         }
-        result.push(min.0);
+    }
+    Ok(decoder)
+}
 
+fn decode(input: &str, order: Order, tie: TieBreak) -> Decoded {
+    let mut decoder = Decoder::default();
+    for line in input.lines() {
+        decoder.push(line);
     }
-    result
+    decoder.decode(order, tie)
+}
+
+fn part1(input: &str) -> String {
+    decode(input, Order::MostCommon, TieBreak::Alphabetical).message
+}
+
+fn part2(input: &str) -> String {
+    decode(input, Order::LeastCommon, TieBreak::Alphabetical).message
 }
 
 fn main() {
+    let args = env::args().collect::<Vec<String>>();
+    let tie = if args.iter().any(|arg| arg == "--reverse") {
+        TieBreak::ReverseAlphabetical
+    } else {
+        TieBreak::Alphabetical
+    };
+    if let Some(idx) = args.iter().position(|arg| arg == "--every") {
+        let every = args
+            .get(idx + 1)
+            .and_then(|arg| arg.parse().ok())
+            .expect("--every should be followed by a line count");
+        let file = File::open("input.txt").expect("input.txt should exist");
+        let decoder = stream(BufReader::new(file), every, |decoder| {
+            println!(
+                "{}: {} {}",
+                decoder.lines,
+                decoder.decode(Order::MostCommon, tie).message,
+                decoder.decode(Order::LeastCommon, tie).message
+            );
+        })
+        .expect("input.txt should be readable");
+        println!("{}", decoder.decode(Order::MostCommon, tie).message);
+        println!("{}", decoder.decode(Order::LeastCommon, tie).message);
+        return;
+    }
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
     println!("{}", part1(&input));
     println!("{}", part2(&input));
+    for order in [Order::MostCommon, Order::LeastCommon] {
+        let decoded = decode(&input, order, tie);
+        let ambiguous = decoded.ambiguous();
+        if ambiguous.is_empty() {
+            continue;
+        }
+        println!(
+            "{} is ambiguous in columns {:?}",
+            decoded.message, ambiguous
+        );
+        for idx in ambiguous {
+            println!("  {}: {:?}", idx, &decoded.columns[idx][..2]);
+        }
+    }
 }
 
 #[cfg(test)]
@@ -72,4 +181,76 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!("advent".to_string(), part2(&input));
     }
+
+    const EXAMPLE: &str = "eedadn\ndrvtee\neandsr\nraavrd\natevrs\ntsrnev\nsdttsa\nrasrtv\nnssdts\nntnada\nsvetve\ntesnvt\nvntsnd\nvrdear\ndvrsen\nenarar";
+
+    #[test]
+    fn test_decode() {
+        let decoded = decode(EXAMPLE, Order::MostCommon, TieBreak::Alphabetical);
+        assert_eq!("easter", decoded.message);
+        assert_eq!(vec![('e', 3), ('d', 2), ('n', 2)], decoded.columns[0][..3]);
+        assert_eq!(Some(1), decoded.margins[0]);
+        assert!(decoded.ambiguous().is_empty());
+        assert_eq!("advent", part2(EXAMPLE));
+    }
+
+    #[test]
+    fn test_ties() {
+        let input = "ab\nba\nac";
+        let decoded = decode(input, Order::MostCommon, TieBreak::Alphabetical);
+        assert_eq!("aa", decoded.message);
+        assert_eq!(vec![Some(1), Some(0)], decoded.margins);
+        assert_eq!(vec![1], decoded.ambiguous());
+        let decoded = decode(input, Order::MostCommon, TieBreak::ReverseAlphabetical);
+        assert_eq!("ac", decoded.message);
+        let decoded = decode(input, Order::LeastCommon, TieBreak::Alphabetical);
+        assert_eq!("ba", decoded.message);
+        assert_eq!(vec![Some(1), Some(0)], decoded.margins);
+        assert_eq!(
+            None,
+            decode("a", Order::MostCommon, TieBreak::Alphabetical).margins[0]
+        );
+    }
+
+    #[test]
+    fn test_ragged() {
+        let decoded = decode(
+            "ab\nabc\na\n\nbbcd",
+            Order::MostCommon,
+            TieBreak::Alphabetical,
+        );
+        assert_eq!("abcd", decoded.message);
+        assert_eq!(vec![4, 3, 2, 1], decoded.samples);
+        assert_eq!(vec![Some(2), None, None, None], decoded.margins);
+    }
+
+    #[test]
+    fn test_stream() {
+        let mut reports = Vec::new();
+        let decoder = stream(EXAMPLE.as_bytes(), 5, |decoder| {
+            reports.push((
+                decoder.lines,
+                decoder
+                    .decode(Order::MostCommon, TieBreak::Alphabetical)
+                    .message,
+            ));
+        })
+        .unwrap();
+        assert_eq!(
+            vec![
+                (5, "eaavrd".to_string()),
+                (10, "eanada".to_string()),
+                (15, "dastea".to_string()),
+            ],
+            reports
+        );
+        assert_eq!(16, decoder.lines);
+        assert_eq!(vec![16; 6], decoder.samples);
+        assert_eq!(
+            decode(EXAMPLE, Order::LeastCommon, TieBreak::Alphabetical),
+            decoder.decode(Order::LeastCommon, TieBreak::Alphabetical)
+        );
+        let decoder = stream("ab\r\nabc\r\n".as_bytes(), 0, |_| panic!()).unwrap();
+        assert_eq!(vec![2, 2, 1], decoder.samples);
+    }
 }
@@ -0,0 +1,46 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+#[allow(dead_code)]
+#[path = "../src/scan.rs"]
+mod scan;
+
+#[path = "../src/fixtures.rs"]
+mod fixtures;
+
+use fixtures::{generate, windowed};
+use scan::Scan;
+
+fn single_pass(address: &str) -> (bool, bool) {
+    let scan = Scan::new(address.as_bytes());
+    (scan.tls(), scan.ssl())
+}
+
+fn bench(c: &mut Criterion) {
+    let addresses = generate(2000, b"abcdefghijklmnopqrstuvwxyz", 20);
+    assert!(addresses
+        .iter()
+        .all(|address| windowed(address) == single_pass(address)));
+    let mut group = c.benchmark_group("ipv7 tls and ssl");
+    group.bench_function("windows", |b| {
+        b.iter(|| {
+            addresses
+                .iter()
+                .map(|address| windowed(address))
+                .filter(|(tls, ssl)| *tls || *ssl)
+                .count()
+        })
+    });
+    group.bench_function("single pass", |b| {
+        b.iter(|| {
+            addresses
+                .iter()
+                .map(|address| single_pass(address))
+                .filter(|(tls, ssl)| *tls || *ssl)
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
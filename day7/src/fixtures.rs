@@ -0,0 +1,68 @@
+pub fn generate(count: usize, alphabet: &[u8], length: usize) -> Vec<String> {
+    let mut seed = 0x2016_0007u64;
+    let mut next = |bound: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % bound
+    };
+    let mut retval = Vec::new();
+    for _ in 0..count {
+        let mut address = String::new();
+        for segment in 0..1 + next(6) {
+            if segment % 2 == 1 {
+                address.push('[');
+            }
+            for _ in 0..1 + next(length) {
+                address.push(alphabet[next(alphabet.len())] as char);
+            }
+            if segment % 2 == 1 {
+                address.push(']');
+            }
+        }
+        retval.push(address);
+    }
+    retval
+}
+
+fn sections(address: &str) -> (Vec<Vec<char>>, Vec<Vec<char>>) {
+    let mut supernets = vec![Vec::new()];
+    let mut hypernets = Vec::new();
+    let mut hypernet = false;
+    for c in address.chars() {
+        match c {
+            '[' => {
+                hypernet = true;
+                hypernets.push(Vec::new());
+            }
+            ']' => {
+                hypernet = false;
+                supernets.push(Vec::new());
+            }
+            _ if hypernet => hypernets.last_mut().unwrap().push(c),
+            _ => supernets.last_mut().unwrap().push(c),
+        }
+    }
+    (supernets, hypernets)
+}
+
+pub fn windowed(address: &str) -> (bool, bool) {
+    let (supernets, hypernets) = sections(address);
+    let abba = |section: &Vec<char>| {
+        section
+            .windows(4)
+            .any(|w| w[0] == w[3] && w[1] == w[2] && w[0] != w[1])
+    };
+    let tls = !hypernets.iter().any(abba) && supernets.iter().any(abba);
+    let abas = supernets
+        .iter()
+        .flat_map(|section| section.windows(3))
+        .filter(|w| w[0] == w[2] && w[0] != w[1])
+        .collect::<Vec<&[char]>>();
+    let ssl = hypernets
+        .iter()
+        .flat_map(|section| section.windows(3))
+        .filter(|w| w[0] == w[2] && w[0] != w[1])
+        .any(|bab| abas.iter().any(|aba| aba[0] == bab[1] && aba[1] == bab[0]));
+    (tls, ssl)
+}
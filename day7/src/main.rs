@@ -1,80 +1,223 @@
+use std::env;
 use std::fs;
+use std::ops::Range;
+
+use scan::Scan;
+
+#[cfg(test)]
+mod fixtures;
+mod scan;
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+enum Kind {
+    Supernet,
+    Hypernet,
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+struct Segment {
+    kind: Kind,
+    start: usize,
+    text: String,
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+enum ParseError {
+    Nested(usize),
+    Unopened(usize),
+    Unclosed(usize),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Segment>, ParseError> {
+    let mut segments = Vec::new();
+    let mut open = None;
+    let mut start = 0;
+    for (idx, c) in input.char_indices() {
+        let kind = match (c, open) {
+            ('[', None) => {
+                open = Some(idx);
+                Kind::Supernet
+            }
+            ('[', Some(_)) => return Err(ParseError::Nested(idx)),
+            (']', Some(_)) => {
+                open = None;
+                Kind::Hypernet
+            }
+            (']', None) => return Err(ParseError::Unopened(idx)),
+            _ => continue,
+        };
+        if start < idx {
+            segments.push(Segment {
+                kind,
+                start,
+                text: input[start..idx].to_string(),
+            });
+        }
+        start = idx + 1;
+    }
+    if let Some(idx) = open {
+        return Err(ParseError::Unclosed(idx));
+    }
+    if start < input.len() {
+        segments.push(Segment {
+            kind: Kind::Supernet,
+            start,
+            text: input[start..].to_string(),
+        });
+    }
+    Ok(segments)
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+enum Verdict {
+    Abba(Range<usize>),
+    HypernetAbba(Range<usize>),
+    NoAbba,
+    AbaBab(Range<usize>, Range<usize>),
+    NoAbaBab,
+}
+
+impl Verdict {
+    fn supported(&self) -> bool {
+        matches!(self, Verdict::Abba(_) | Verdict::AbaBab(_, _))
+    }
+
+    fn spans(&self) -> Vec<Range<usize>> {
+        match self {
+            Verdict::Abba(span) | Verdict::HypernetAbba(span) => vec![span.clone()],
+            Verdict::AbaBab(aba, bab) => {
+                let mut retval = vec![aba.clone(), bab.clone()];
+                retval.sort_by_key(|span| span.start);
+                retval
+            }
+            Verdict::NoAbba | Verdict::NoAbaBab => Vec::new(),
+        }
+    }
+}
+
+fn find_abba(segment: &Segment) -> Option<Range<usize>> {
+    segment
+        .text
+        .as_bytes()
+        .windows(4)
+        .position(|w| {
+            w[0] == w[3]
+                && w[1] == w[2]
+                && w[0] != w[1]
+                && w[..2].iter().all(u8::is_ascii_lowercase)
+        })
+        .map(|idx| segment.start + idx..segment.start + idx + 4)
+}
+
+fn abas(segment: &Segment) -> impl Iterator<Item = (Range<usize>, [u8; 2])> + '_ {
+    segment
+        .text
+        .as_bytes()
+        .windows(3)
+        .enumerate()
+        .filter(|(_, w)| w[0] == w[2] && w[0] != w[1] && w[..2].iter().all(u8::is_ascii_lowercase))
+        .map(|(idx, w)| (segment.start + idx..segment.start + idx + 3, [w[0], w[1]]))
+}
 
 #[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
 struct IPv7 {
     raw: String,
-    address: Vec<String>,
-    hypernet_sequences: Vec<String>,
+    segments: Vec<Segment>,
+    scan: Scan,
 }
 
 impl IPv7 {
-    fn new(input: &str) -> Self {
-        let local = input.to_string().replace("[", " ").replace("]", " ");
-        let mut address = Vec::new();
-        let mut hypernet_sequences = Vec::new();
-        for (idx, split) in local.split(" ").enumerate() {
-            if idx % 2 == 0 {
-                address.push(split.to_string());
-            } else {
-                hypernet_sequences.push(split.to_string());
-            }
-        }
-        IPv7 {
+    fn new(input: &str) -> Result<Self, ParseError> {
+        Ok(IPv7 {
             raw: input.to_string(),
-            address,
-            hypernet_sequences,
-        }
+            segments: tokenize(input)?,
+            scan: Scan::new(input.as_bytes()),
+        })
     }
 
     fn supports_tls(&self) -> bool {
-        for sequence in &self.hypernet_sequences {
-            for chunk in sequence.chars().collect::<Vec<char>>().windows(4) {
-                if chunk[0] == chunk[3] && chunk[1] == chunk[2] && chunk[0] != chunk[1] {
-                    return false;
-                }
-            }
+        self.scan.tls()
+    }
+
+    fn supports_ssl(&self) -> bool {
+        self.scan.ssl()
+    }
+
+    fn segments(&self, kind: Kind) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(move |segment| segment.kind == kind)
+    }
+
+    fn explain_tls(&self) -> Verdict {
+        if let Some(span) = self.segments(Kind::Hypernet).find_map(find_abba) {
+            return Verdict::HypernetAbba(span);
         }
-        for section in &self.address {
-            for chunk in section.chars().collect::<Vec<char>>().windows(4) {
-                if chunk[0] == chunk[3] && chunk[1] == chunk[2] && chunk[0] != chunk[1] {
-                    return true;
-                }
-            }
+        match self.segments(Kind::Supernet).find_map(find_abba) {
+            Some(span) => Verdict::Abba(span),
+            None => Verdict::NoAbba,
         }
-        false
     }
 
-    fn supports_ssl(&self) -> bool {
-        let mut abas = Vec::new();
-        for section in &self.address {
-            for chunk in section.chars().collect::<Vec<char>>().windows(3) {
-                if chunk[0] == chunk[2] && chunk[0] != chunk[1] {
-                    abas.push(chunk.to_vec());
-                }
+    fn explain_ssl(&self) -> Verdict {
+        for (aba, [a, b]) in self.segments(Kind::Supernet).flat_map(abas) {
+            let bab = self
+                .segments(Kind::Hypernet)
+                .flat_map(abas)
+                .find(|(_, pair)| *pair == [b, a]);
+            if let Some((bab, _)) = bab {
+                return Verdict::AbaBab(aba, bab);
             }
         }
-        if abas.len() == 0 {
-            return false;
+        Verdict::NoAbaBab
+    }
+
+    fn describe(&self, verdict: &Verdict) -> String {
+        match verdict {
+            Verdict::Abba(span) => format!(
+                "supports TLS: ABBA {} at {}",
+                &self.raw[span.clone()],
+                span.start
+            ),
+            Verdict::HypernetAbba(span) => format!(
+                "no TLS: ABBA {} inside a hypernet at {}",
+                &self.raw[span.clone()],
+                span.start
+            ),
+            Verdict::NoAbba => "no TLS: no ABBA outside the hypernets".to_string(),
+            Verdict::AbaBab(aba, bab) => format!(
+                "supports SSL: ABA {} at {} matches BAB {} at {}",
+                &self.raw[aba.clone()],
+                aba.start,
+                &self.raw[bab.clone()],
+                bab.start
+            ),
+            Verdict::NoAbaBab => "no SSL: no ABA with a matching BAB".to_string(),
         }
-        for sequence in &self.hypernet_sequences {
-            for chunk in sequence.chars().collect::<Vec<char>>().windows(3) {
-                if chunk[0] == chunk[2] && chunk[0] != chunk[1] {
-                    for aba in &abas {
-                        if aba[1] == chunk[0] && aba[0] == chunk[1] {
-                            return true;
-                        }
-                    }
-                }
-            }
+    }
+
+    fn highlight(&self, verdict: &Verdict) -> String {
+        let colour = if verdict.supported() {
+            "\x1b[1;32m"
+        } else {
+            "\x1b[1;31m"
+        };
+        let mut retval = String::new();
+        let mut last = 0;
+        for span in verdict.spans() {
+            retval.push_str(&self.raw[last..span.start]);
+            retval.push_str(&format!("{}{}\x1b[0m", colour, &self.raw[span.clone()]));
+            last = span.end;
         }
-        false
+        retval.push_str(&self.raw[last..]);
+        retval
     }
 }
 
 fn part1(input: &str) -> usize {
     let mut ip_addrs = Vec::new();
     for line in input.lines() {
-        ip_addrs.push(IPv7::new(line));
+        ip_addrs.push(IPv7::new(line).expect("address should be valid"));
     }
     ip_addrs
         .iter()
@@ -87,7 +230,7 @@ fn part1(input: &str) -> usize {
 fn part2(input: &str) -> usize {
     let mut ip_addrs = Vec::new();
     for line in input.lines() {
-        ip_addrs.push(IPv7::new(line));
+        ip_addrs.push(IPv7::new(line).expect("address should be valid"));
     }
     ip_addrs
         .iter()
@@ -98,6 +241,29 @@ fn part2(input: &str) -> usize {
 }
 
 fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.first().map(String::as_str) == Some("explain") {
+        let mut addresses = args[1..].to_vec();
+        if addresses.is_empty() {
+            let input = fs::read_to_string("input.txt").expect("input.txt should exist");
+            addresses = input.lines().map(str::to_string).collect();
+        }
+        for raw in addresses {
+            match IPv7::new(&raw) {
+                Ok(address) => {
+                    for verdict in [address.explain_tls(), address.explain_ssl()] {
+                        println!(
+                            "{}  {}",
+                            address.highlight(&verdict),
+                            address.describe(&verdict)
+                        );
+                    }
+                }
+                Err(err) => println!("{}  invalid: {:?}", raw, err),
+            }
+        }
+        return;
+    }
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
     println!("{}", part1(&input));
     println!("{}", part2(&input));
@@ -105,6 +271,7 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use super::fixtures::{generate, windowed};
     use super::*;
 
     #[test]
@@ -118,4 +285,164 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!(3, part2(&input));
     }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            Ok(vec![
+                Segment {
+                    kind: Kind::Supernet,
+                    start: 0,
+                    text: "abba".to_string()
+                },
+                Segment {
+                    kind: Kind::Hypernet,
+                    start: 5,
+                    text: "mnop".to_string()
+                },
+                Segment {
+                    kind: Kind::Supernet,
+                    start: 10,
+                    text: "qrst".to_string()
+                },
+            ]),
+            tokenize("abba[mnop]qrst")
+        );
+        let kinds = |input| {
+            tokenize(input)
+                .unwrap()
+                .iter()
+                .map(|segment| (segment.kind, segment.start))
+                .collect::<Vec<(Kind, usize)>>()
+        };
+        assert_eq!(
+            vec![(Kind::Hypernet, 1), (Kind::Supernet, 5)],
+            kinds("[abc]def")
+        );
+        assert_eq!(
+            vec![
+                (Kind::Supernet, 0),
+                (Kind::Hypernet, 3),
+                (Kind::Hypernet, 7),
+                (Kind::Supernet, 10)
+            ],
+            kinds("ab[cd][ef]gh")
+        );
+        assert_eq!(vec![(Kind::Supernet, 0)], kinds("ab[]"));
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        assert_eq!(Err(ParseError::Nested(4)), tokenize("ab[c[d]]e"));
+        assert_eq!(Err(ParseError::Unopened(2)), tokenize("ab]cd"));
+        assert_eq!(Err(ParseError::Unclosed(2)), tokenize("ab[cd"));
+        assert_eq!(Err(ParseError::Unclosed(4)), IPv7::new("a[b]["));
+    }
+
+    #[test]
+    fn test_support() {
+        assert!(IPv7::new("abba[mnop]qrst").unwrap().supports_tls());
+        assert!(!IPv7::new("abcd[bddb]xyyx").unwrap().supports_tls());
+        assert!(!IPv7::new("aaaa[qwer]tyui").unwrap().supports_tls());
+        assert!(IPv7::new("ioxxoj[asdfgh]zxcvbn").unwrap().supports_tls());
+        assert!(!IPv7::new("[abba]qrst").unwrap().supports_tls());
+        assert!(IPv7::new("qrst[mnop][wxyz]abba").unwrap().supports_tls());
+        assert!(IPv7::new("aba[bab]xyz").unwrap().supports_ssl());
+        assert!(!IPv7::new("xyx[xyx]xyx").unwrap().supports_ssl());
+        assert!(IPv7::new("aaa[kek]eke").unwrap().supports_ssl());
+        assert!(IPv7::new("zazbz[bzb]cdb").unwrap().supports_ssl());
+        assert!(IPv7::new("[aba]bab").unwrap().supports_ssl());
+    }
+
+    #[test]
+    fn test_scan_matches_windows() {
+        let addresses = generate(5000, b"abc", 10);
+        let mut tls = 0;
+        let mut ssl = 0;
+        for raw in &addresses {
+            let address = IPv7::new(raw).unwrap();
+            assert_eq!(
+                windowed(raw),
+                (address.supports_tls(), address.supports_ssl()),
+                "{}",
+                raw
+            );
+            tls += address.supports_tls() as usize;
+            ssl += address.supports_ssl() as usize;
+        }
+        assert!(tls > 0 && tls < addresses.len());
+        assert!(ssl > 0 && ssl < addresses.len());
+    }
+
+    #[test]
+    fn test_explain() {
+        let address = IPv7::new("abba[mnop]qrst").unwrap();
+        assert_eq!(Verdict::Abba(0..4), address.explain_tls());
+        assert_eq!(
+            "supports TLS: ABBA abba at 0",
+            address.describe(&address.explain_tls())
+        );
+        let address = IPv7::new("abcd[bddb]xyyx").unwrap();
+        assert_eq!(Verdict::HypernetAbba(5..9), address.explain_tls());
+        assert_eq!(
+            "abcd[\x1b[1;31mbddb\x1b[0m]xyyx",
+            address.highlight(&address.explain_tls())
+        );
+        assert_eq!(
+            Verdict::NoAbba,
+            IPv7::new("aaaa[qwer]tyui").unwrap().explain_tls()
+        );
+        let address = IPv7::new("zazbz[bzb]cdb").unwrap();
+        assert_eq!(Verdict::AbaBab(2..5, 6..9), address.explain_ssl());
+        assert_eq!(
+            "supports SSL: ABA zbz at 2 matches BAB bzb at 6",
+            address.describe(&address.explain_ssl())
+        );
+        assert_eq!(
+            "za\x1b[1;32mzbz\x1b[0m[\x1b[1;32mbzb\x1b[0m]cdb",
+            address.highlight(&address.explain_ssl())
+        );
+        let address = IPv7::new("[aba]bab").unwrap();
+        assert_eq!(Verdict::AbaBab(5..8, 1..4), address.explain_ssl());
+        assert_eq!(vec![1..4, 5..8], address.explain_ssl().spans());
+        let address = IPv7::new("xyx[xyx]xyx").unwrap();
+        assert_eq!(Verdict::NoAbaBab, address.explain_ssl());
+        assert_eq!("xyx[xyx]xyx", address.highlight(&address.explain_ssl()));
+    }
+
+    #[test]
+    fn test_explain_non_ascii() {
+        for raw in ["éé[éé]", "aéa[éaé]", "ab©©ba[xy]", "xéy[ÿ]ababa[bab]"] {
+            let address = IPv7::new(raw).unwrap();
+            for verdict in [address.explain_tls(), address.explain_ssl()] {
+                address.describe(&verdict);
+                address.highlight(&verdict);
+            }
+            assert_eq!(address.supports_tls(), address.explain_tls().supported());
+            assert_eq!(address.supports_ssl(), address.explain_ssl().supported());
+        }
+        assert_eq!(
+            Verdict::NoAbaBab,
+            IPv7::new("éé[éé]").unwrap().explain_ssl()
+        );
+        assert_eq!(
+            Verdict::NoAbba,
+            IPv7::new("ab©©ba[xy]").unwrap().explain_tls()
+        );
+        let address = IPv7::new("xéy[ÿ]ababa[bab]").unwrap();
+        assert_eq!(Verdict::AbaBab(8..11, 14..17), address.explain_ssl());
+        assert_eq!(
+            "supports SSL: ABA aba at 8 matches BAB bab at 14",
+            address.describe(&address.explain_ssl())
+        );
+    }
+
+    #[test]
+    fn test_explain_matches_scan() {
+        for raw in generate(2000, b"abc", 10) {
+            let address = IPv7::new(&raw).unwrap();
+            assert_eq!(address.supports_tls(), address.explain_tls().supported());
+            assert_eq!(address.supports_ssl(), address.explain_ssl().supported());
+        }
+    }
 }
@@ -0,0 +1,51 @@
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Scan {
+    pub abba: bool,
+    pub hypernet_abba: bool,
+    pub aba: [u32; 26],
+    pub bab: [u32; 26],
+}
+
+impl Scan {
+    pub fn new(raw: &[u8]) -> Self {
+        let mut scan = Scan::default();
+        let mut hypernet = false;
+        let mut window = [0u8; 3];
+        for &byte in raw {
+            if byte == b'[' || byte == b']' {
+                hypernet = byte == b'[';
+                window = [0; 3];
+                continue;
+            }
+            let [a, b, c] = window;
+            if a == byte && b == c && a != b && a.is_ascii_lowercase() && b.is_ascii_lowercase() {
+                if hypernet {
+                    scan.hypernet_abba = true;
+                } else {
+                    scan.abba = true;
+                }
+            }
+            if b == byte && c != byte && byte.is_ascii_lowercase() && c.is_ascii_lowercase() {
+                let (outer, inner) = ((byte - b'a') as usize, (c - b'a') as usize);
+                if hypernet {
+                    scan.bab[inner] |= 1 << outer;
+                } else {
+                    scan.aba[outer] |= 1 << inner;
+                }
+            }
+            window = [b, c, byte];
+        }
+        scan
+    }
+
+    pub fn tls(&self) -> bool {
+        self.abba && !self.hypernet_abba
+    }
+
+    pub fn ssl(&self) -> bool {
+        self.aba
+            .iter()
+            .zip(self.bab.iter())
+            .any(|(aba, bab)| aba & bab != 0)
+    }
+}
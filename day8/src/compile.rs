@@ -0,0 +1,245 @@
+use super::{Command, Display, Operation};
+
+struct Builder {
+    display: Display,
+    commands: Vec<Command>,
+    pending: Vec<usize>,
+}
+
+impl Builder {
+    fn emit(&mut self, operation: Operation, a: usize, b: usize) {
+        let command = Command::new(operation, a, b);
+        self.display
+            .execute(command)
+            .expect("synthesized commands should fit the screen");
+        self.commands.push(command);
+    }
+
+    fn flush(&mut self, row: usize) {
+        let by = self.pending[row] % self.display.width;
+        self.pending[row] = 0;
+        if by > 0 && self.display.grid[row].iter().any(|cell| cell.on()) {
+            self.emit(Operation::RotateRow, row, by);
+        }
+    }
+
+    fn draw_column(&mut self, lit: &[usize]) {
+        let mut runs: Vec<(usize, usize)> = Vec::new();
+        for &y in lit {
+            match runs.last_mut() {
+                Some((start, len)) if *start + *len == y => *len += 1,
+                _ => runs.push((y, 1)),
+            }
+        }
+        for (idx, &(start, len)) in runs.iter().enumerate().rev() {
+            self.emit(Operation::Rect, 1, len);
+            let by = if idx > 0 {
+                start - runs[idx - 1].0
+            } else {
+                start
+            };
+            if by > 0 {
+                self.emit(Operation::RotateColumn, 0, by);
+            }
+        }
+    }
+}
+
+fn by_columns(target: &[Vec<bool>], width: usize, height: usize) -> Vec<Command> {
+    let mut builder = Builder {
+        display: Display::new(width, height),
+        commands: Vec::new(),
+        pending: vec![0; height],
+    };
+    for x in (0..width).rev() {
+        let lit = (0..height)
+            .filter(|&y| target[y][x])
+            .collect::<Vec<usize>>();
+        if !lit.is_empty() {
+            for (y, row) in target.iter().enumerate() {
+                if row[x] || builder.display.grid[y][0].on() {
+                    builder.flush(y);
+                }
+            }
+            builder.draw_column(&lit);
+        }
+        if x > 0 {
+            builder.pending.iter_mut().for_each(|pending| *pending += 1);
+        }
+    }
+    for y in 0..height {
+        builder.flush(y);
+    }
+    builder.commands
+}
+
+fn transpose(target: &[Vec<bool>], width: usize, height: usize) -> Vec<Vec<bool>> {
+    (0..width)
+        .map(|x| (0..height).map(|y| target[y][x]).collect())
+        .collect()
+}
+
+fn swap(command: Command) -> Command {
+    let operation = match command.operation {
+        Operation::Rect | Operation::RectOff | Operation::Invert => {
+            return Command {
+                operation: command.operation,
+                a: command.b,
+                b: command.a,
+                x: command.y,
+                y: command.x,
+            }
+        }
+        Operation::RotateRow => Operation::RotateColumn,
+        Operation::RotateColumn => Operation::RotateRow,
+        Operation::ShiftRow => Operation::ShiftColumn,
+        Operation::ShiftColumn => Operation::ShiftRow,
+        Operation::FlipHorizontal => Operation::FlipVertical,
+        Operation::FlipVertical => Operation::FlipHorizontal,
+    };
+    Command {
+        operation,
+        ..command
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum CompileError {
+    Ragged(usize, usize, usize),
+}
+
+pub fn compile(target: &[Vec<bool>]) -> Result<Vec<Command>, CompileError> {
+    let height = target.len();
+    let width = target.first().map_or(0, Vec::len);
+    if let Some((y, row)) = target
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != width)
+    {
+        return Err(CompileError::Ragged(y, row.len(), width));
+    }
+    if target.iter().flatten().all(|on| !on) {
+        return Ok(Vec::new());
+    }
+    let a = target[0].iter().take_while(|on| **on).count();
+    let b = target.iter().take_while(|row| row[0]).count();
+    let rect = Command::new(Operation::Rect, a, b);
+    if verify(target, &[rect]) {
+        return Ok(vec![rect]);
+    }
+    let columns = by_columns(target, width, height);
+    let rows = by_columns(&transpose(target, width, height), height, width)
+        .into_iter()
+        .map(swap)
+        .collect::<Vec<Command>>();
+    if rows.len() < columns.len() {
+        Ok(rows)
+    } else {
+        Ok(columns)
+    }
+}
+
+pub fn verify(target: &[Vec<bool>], commands: &[Command]) -> bool {
+    let mut display = Display::new(target.first().map_or(0, Vec::len), target.len());
+    for command in commands {
+        if display.execute(*command).is_err() {
+            return false;
+        }
+    }
+    display.pixels() == target
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ocr;
+    use super::*;
+
+    fn bitmap(width: usize, height: usize, bits: u64) -> Vec<Vec<bool>> {
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| bits >> (y * width + x) & 1 == 1)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn pattern(width: usize, height: usize, step: usize) -> Vec<Vec<bool>> {
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| (x * x + 3 * y + x * y) % step == 0)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_compile_text() {
+        let target = ocr::draw("ZJHRKCPLYJ").unwrap();
+        let commands = compile(&target).unwrap();
+        assert!(verify(&target, &commands));
+        let mut display = Display::new(50, 6);
+        for command in &commands {
+            display.execute(*command).unwrap();
+        }
+        assert_eq!(Ok("ZJHRKCPLYJ".to_string()), display.read());
+    }
+
+    #[test]
+    fn test_compile_small() {
+        assert_eq!(Ok(Vec::new()), compile(&vec![vec![false; 3]; 2]));
+        let target = vec![vec![true, true, false], vec![true, true, false]];
+        assert_eq!(
+            Ok(vec![Command::from("rect 2x2").unwrap()]),
+            compile(&target)
+        );
+        let target = vec![vec![false, true], vec![false, false], vec![false, true]];
+        let commands = compile(&target).unwrap();
+        assert!(verify(&target, &commands));
+        assert!(commands.len() <= 5);
+    }
+
+    #[test]
+    fn test_compile_ragged() {
+        let target = vec![vec![true, false, true], vec![true]];
+        assert_eq!(Err(CompileError::Ragged(1, 1, 3)), compile(&target));
+        assert!(!verify(&target, &[]));
+        let target = vec![vec![false], vec![false, true]];
+        assert_eq!(Err(CompileError::Ragged(1, 2, 1)), compile(&target));
+    }
+
+    #[test]
+    fn test_compile_exhaustive() {
+        for (width, height) in [(1, 1), (2, 3), (3, 2), (4, 3), (3, 4)] {
+            for bits in 0..1u64 << (width * height) {
+                let target = bitmap(width, height, bits);
+                let commands = compile(&target).unwrap();
+                assert!(
+                    verify(&target, &commands),
+                    "{}x{} {:b}",
+                    width,
+                    height,
+                    bits
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compile_patterns() {
+        for (width, height) in [(7, 3), (3, 7), (50, 6), (13, 11)] {
+            for step in 2..7 {
+                let target = pattern(width, height, step);
+                let commands = compile(&target).unwrap();
+                assert!(
+                    verify(&target, &commands),
+                    "{}x{} step {}",
+                    width,
+                    height,
+                    step
+                );
+            }
+        }
+    }
+}
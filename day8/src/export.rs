@@ -0,0 +1,193 @@
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use gif::{Encoder, EncodingError, Frame, Repeat};
+
+use super::Display;
+
+const PALETTE: [u8; 6] = [0x10, 0x10, 0x10, 0x4c, 0xff, 0x4c];
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    Gif(EncodingError),
+    TooLarge(usize, usize, usize),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "{}", err),
+            ExportError::Gif(err) => write!(f, "{}", err),
+            ExportError::TooLarge(width, height, scale) => {
+                write!(f, "{}x{} at scale {} is too large", width, height, scale)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for ExportError {
+    fn from(err: io::Error) -> Self {
+        ExportError::Io(err)
+    }
+}
+
+impl From<EncodingError> for ExportError {
+    fn from(err: EncodingError) -> Self {
+        ExportError::Gif(err)
+    }
+}
+
+fn pixels(display: &Display, scale: usize) -> Vec<Vec<bool>> {
+    let mut retval = Vec::new();
+    for row in &display.grid {
+        let scaled = row
+            .iter()
+            .flat_map(|cell| std::iter::repeat_n(cell.on(), scale))
+            .collect::<Vec<bool>>();
+        for _ in 0..scale {
+            retval.push(scaled.clone());
+        }
+    }
+    retval
+}
+
+fn scaled(display: &Display, scale: usize) -> Result<(usize, usize), ExportError> {
+    let too_large = || ExportError::TooLarge(display.width, display.height, scale);
+    let width = display.width.checked_mul(scale).ok_or_else(too_large)?;
+    let height = display.height.checked_mul(scale).ok_or_else(too_large)?;
+    Ok((width, height))
+}
+
+pub fn pbm(display: &Display, scale: usize) -> Result<Vec<u8>, ExportError> {
+    let (width, height) = scaled(display, scale)?;
+    let mut retval = format!("P4\n{} {}\n", width, height).into_bytes();
+    for row in pixels(display, scale) {
+        for chunk in row.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (idx, on)| acc | ((*on as u8) << (7 - idx)));
+            retval.push(byte);
+        }
+    }
+    Ok(retval)
+}
+
+pub fn write_frames(
+    frames: &[Display],
+    dir: &Path,
+    scale: usize,
+) -> Result<Vec<PathBuf>, ExportError> {
+    if let Some(display) = frames.first() {
+        scaled(display, scale)?;
+    }
+    fs::create_dir_all(dir)?;
+    let mut retval = Vec::new();
+    for (idx, display) in frames.iter().enumerate() {
+        let path = dir.join(format!("frame_{:04}.pbm", idx));
+        fs::write(&path, pbm(display, scale)?)?;
+        retval.push(path);
+    }
+    Ok(retval)
+}
+
+pub fn write_gif(
+    frames: &[Display],
+    path: &Path,
+    scale: usize,
+    delay: u16,
+) -> Result<(), ExportError> {
+    let Some(first) = frames.first() else {
+        return Ok(());
+    };
+    let (width, height) = scaled(first, scale)?;
+    let too_large = || ExportError::TooLarge(first.width, first.height, scale);
+    let gif_width = u16::try_from(width).map_err(|_| too_large())?;
+    let gif_height = u16::try_from(height).map_err(|_| too_large())?;
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = Encoder::new(file, gif_width, gif_height, &PALETTE)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+    for display in frames {
+        let buffer = pixels(display, scale)
+            .into_iter()
+            .flatten()
+            .map(|on| on as u8)
+            .collect::<Vec<u8>>();
+        let frame = Frame {
+            width: gif_width,
+            height: gif_height,
+            delay,
+            buffer: buffer.into(),
+            ..Default::default()
+        };
+        encoder.write_frame(&frame)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate_history;
+    use super::*;
+
+    #[test]
+    fn test_pbm() {
+        let frames = generate_history("rect 3x2", 7, 3).unwrap();
+        assert_eq!(
+            b"P4\n7 3\n\xe0\xe0\x00".to_vec(),
+            pbm(&frames[0], 1).unwrap()
+        );
+        let scaled = pbm(&frames[0], 3).unwrap();
+        assert!(scaled.starts_with(b"P4\n21 9\n"));
+        let rows = &scaled[8..];
+        assert_eq!(9 * 3, rows.len());
+        assert_eq!([0xff, 0x80, 0x00], rows[..3]);
+        assert_eq!(rows[..3], rows[15..18]);
+        assert_eq!([0, 0, 0], rows[18..21]);
+    }
+
+    #[test]
+    fn test_write() {
+        let input = "rect 3x2\nrotate column x=1 by 1\nrotate row y=0 by 4";
+        let frames = generate_history(input, 7, 3).unwrap();
+        let dir = std::env::temp_dir().join(format!("day8-export-{}", std::process::id()));
+        let paths = write_frames(&frames, &dir, 2).unwrap();
+        assert_eq!(3, paths.len());
+        assert!(paths[2].ends_with("frame_0002.pbm"));
+        assert_eq!(pbm(&frames[1], 2).unwrap(), fs::read(&paths[1]).unwrap());
+        let gif = dir.join("screen.gif");
+        write_gif(&frames, &gif, 2, 10).unwrap();
+        let bytes = fs::read(&gif).unwrap();
+        assert!(bytes.starts_with(b"GIF89a"));
+        assert_eq!([14, 0, 6, 0], bytes[6..10]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_too_large() {
+        let frames = generate_history("rect 3x2", 7, 3).unwrap();
+        let scale = usize::MAX / 3;
+        assert!(matches!(
+            pbm(&frames[0], scale),
+            Err(ExportError::TooLarge(7, 3, s)) if s == scale
+        ));
+        let dir = std::env::temp_dir().join(format!("day8-large-{}", std::process::id()));
+        assert!(matches!(
+            write_frames(&frames, &dir, scale),
+            Err(ExportError::TooLarge(7, 3, _))
+        ));
+        assert!(!dir.exists());
+        let gif = dir.join("screen.gif");
+        assert!(matches!(
+            write_gif(&frames, &gif, scale, 10),
+            Err(ExportError::TooLarge(7, 3, _))
+        ));
+        assert!(matches!(
+            write_gif(&frames, &gif, 10000, 10),
+            Err(ExportError::TooLarge(7, 3, 10000))
+        ));
+    }
+}
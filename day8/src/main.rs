@@ -1,4 +1,13 @@
+use std::env;
+use std::fmt;
 use std::fs;
+use std::path::Path;
+
+use ocr::OcrError;
+
+mod compile;
+mod export;
+mod ocr;
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 enum State {
@@ -10,13 +19,32 @@ impl State {
     fn on(&self) -> bool {
         *self == State::On
     }
+
+    fn toggle(&self) -> State {
+        match self {
+            State::On => State::Off,
+            State::Off => State::On,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 enum Operation {
     Rect,
+    RectOff,
+    Invert,
     RotateRow,
     RotateColumn,
+    ShiftRow,
+    ShiftColumn,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+enum CommandError {
+    UnknownVerb(String),
+    Malformed(String),
 }
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -24,94 +52,201 @@ struct Command {
     operation: Operation,
     a: usize,
     b: usize,
+    x: usize,
+    y: usize,
 }
 
 impl Command {
-    fn from(input: &str) -> Self {
-        let operation;
-        let a;
-        let b;
-        if input.contains("rect") {
-            operation = Operation::Rect;
-            let mut splits = input.split(" ");
-            splits.next();
-            let mut splits = splits.next().unwrap().split("x");
-            a = splits.next().unwrap().parse().unwrap();
-            b = splits.next().unwrap().parse().unwrap();
-        } else {
-            let mut splits = input.split(" ");
-            splits.next();
-            if splits.next().unwrap() == "row" {
-                operation = Operation::RotateRow;
-            } else {
-                operation = Operation::RotateColumn;
+    fn new(operation: Operation, a: usize, b: usize) -> Self {
+        Command {
+            operation,
+            a,
+            b,
+            x: 0,
+            y: 0,
+        }
+    }
+
+    fn from(input: &str) -> Result<Self, CommandError> {
+        let malformed = || CommandError::Malformed(input.to_string());
+        let number = |text: &str| text.parse::<usize>().map_err(|_| malformed());
+        let pair = |text: &str, separator: char| {
+            let (first, second) = text.split_once(separator).ok_or_else(malformed)?;
+            Ok::<_, CommandError>((number(first)?, number(second)?))
+        };
+        let words = input.split_whitespace().collect::<Vec<&str>>();
+        match words.as_slice() {
+            [verb @ ("rect" | "rect-off" | "invert"), size, rest @ ..] => {
+                let operation = match *verb {
+                    "rect" => Operation::Rect,
+                    "rect-off" => Operation::RectOff,
+                    _ => Operation::Invert,
+                };
+                let (a, b) = pair(size, 'x')?;
+                let (x, y) = match rest {
+                    [] => (0, 0),
+                    ["at", origin] => pair(origin, ',')?,
+                    _ => return Err(malformed()),
+                };
+                Ok(Command {
+                    operation,
+                    a,
+                    b,
+                    x,
+                    y,
+                })
             }
-            a = splits
-                .next()
-                .unwrap()
-                .replace("y=", "")
-                .replace("x=", "")
-                .parse()
-                .unwrap();
-            splits.next();
-            b = splits.next().unwrap().parse().unwrap();
+            [verb @ ("rotate" | "shift"), axis, target, "by", amount] => {
+                let (operation, prefix) = match (*verb, *axis) {
+                    ("rotate", "row") => (Operation::RotateRow, "y="),
+                    ("rotate", "column") => (Operation::RotateColumn, "x="),
+                    ("shift", "row") => (Operation::ShiftRow, "y="),
+                    ("shift", "column") => (Operation::ShiftColumn, "x="),
+                    _ => return Err(malformed()),
+                };
+                let a = number(target.strip_prefix(prefix).ok_or_else(malformed)?)?;
+                Ok(Command::new(operation, a, number(amount)?))
+            }
+            ["flip", "horizontal"] => Ok(Command::new(Operation::FlipHorizontal, 0, 0)),
+            ["flip", "vertical"] => Ok(Command::new(Operation::FlipVertical, 0, 0)),
+            ["rect" | "rect-off" | "invert" | "rotate" | "shift" | "flip", ..] | [] => {
+                Err(malformed())
+            }
+            [verb, ..] => Err(CommandError::UnknownVerb(verb.to_string())),
         }
-        Command { operation, a, b }
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (verb, axis) = match self.operation {
+            Operation::Rect => ("rect", None),
+            Operation::RectOff => ("rect-off", None),
+            Operation::Invert => ("invert", None),
+            Operation::RotateRow => ("rotate", Some("row y")),
+            Operation::RotateColumn => ("rotate", Some("column x")),
+            Operation::ShiftRow => ("shift", Some("row y")),
+            Operation::ShiftColumn => ("shift", Some("column x")),
+            Operation::FlipHorizontal => return write!(f, "flip horizontal"),
+            Operation::FlipVertical => return write!(f, "flip vertical"),
+        };
+        match axis {
+            Some(axis) => write!(f, "{} {}={} by {}", verb, axis, self.a, self.b),
+            None if self.x == 0 && self.y == 0 => write!(f, "{} {}x{}", verb, self.a, self.b),
+            None => write!(f, "{} {}x{} at {},{}", verb, self.a, self.b, self.x, self.y),
+        }
+    }
+}
+
+const WIDTH: usize = 50;
+const HEIGHT: usize = 6;
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+enum ScreenError {
+    BadCommand(CommandError),
+    EmptyScreen(usize, usize),
+    RectTooWide(usize),
+    RectTooTall(usize),
+    RowOutOfRange(usize),
+    ColumnOutOfRange(usize),
+}
+
+fn shift(line: &mut [State], by: usize) {
+    let by = by.min(line.len());
+    line.rotate_right(by);
+    line[..by].fill(State::Off);
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
 struct Display {
-    grid: [[State; 50]; 6],
+    width: usize,
+    height: usize,
+    grid: Vec<Vec<State>>,
 }
 
 impl Display {
-    fn new() -> Display {
-        let grid = [[State::Off; 50]; 6];
-        Display { grid }
+    fn new(width: usize, height: usize) -> Display {
+        let grid = vec![vec![State::Off; width]; height];
+        Display {
+            width,
+            height,
+            grid,
+        }
     }
 
-    fn execute(&mut self, command: Command) {
+    fn check(&self, command: Command) -> Result<(), ScreenError> {
+        if self.width == 0 || self.height == 0 {
+            return Err(ScreenError::EmptyScreen(self.width, self.height));
+        }
         match command.operation {
-            Operation::Rect => {
-                for y in 0..command.b {
-                    for x in 0..command.a {
-                        self.grid[y][x] = State::On;
-                    }
+            Operation::Rect | Operation::RectOff | Operation::Invert => {
+                let right = command.x.saturating_add(command.a);
+                let bottom = command.y.saturating_add(command.b);
+                if right > self.width {
+                    Err(ScreenError::RectTooWide(right))
+                } else if bottom > self.height {
+                    Err(ScreenError::RectTooTall(bottom))
+                } else {
+                    Ok(())
                 }
             }
-            Operation::RotateRow => {
-                let row = command.a;
-                for _ in 0..command.b {
-                    let old_edge = self.grid[row][49];
-                    for x in (0..50).rev() {
-                        if x == 0 {
-                            self.grid[row][x] = old_edge;
-                        } else {
-                            self.grid[row][x] = self.grid[row][x - 1];
-                        }
+            Operation::RotateRow | Operation::ShiftRow if command.a >= self.height => {
+                Err(ScreenError::RowOutOfRange(command.a))
+            }
+            Operation::RotateColumn | Operation::ShiftColumn if command.a >= self.width => {
+                Err(ScreenError::ColumnOutOfRange(command.a))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn column(&self, col: usize) -> Vec<State> {
+        self.grid.iter().map(|row| row[col]).collect()
+    }
+
+    fn set_column(&mut self, col: usize, column: Vec<State>) {
+        for (row, cell) in self.grid.iter_mut().zip(column) {
+            row[col] = cell;
+        }
+    }
+
+    fn execute(&mut self, command: Command) -> Result<(), ScreenError> {
+        self.check(command)?;
+        match command.operation {
+            Operation::Rect | Operation::RectOff | Operation::Invert => {
+                for row in &mut self.grid[command.y..command.y + command.b] {
+                    for cell in &mut row[command.x..command.x + command.a] {
+                        *cell = match command.operation {
+                            Operation::Rect => State::On,
+                            Operation::RectOff => State::Off,
+                            _ => cell.toggle(),
+                        };
                     }
                 }
             }
+            Operation::RotateRow => {
+                self.grid[command.a].rotate_right(command.b % self.width);
+            }
             Operation::RotateColumn => {
-                let col = command.a;
-                for _ in 0..command.b {
-                    let old_edge = self.grid[5][col];
-                    for y in (0..6).rev() {
-                        if y == 0 {
-                            self.grid[y][col] = old_edge;
-                        } else {
-                            self.grid[y][col] = self.grid[y-1][col];
-                        }
-                    }
-                }
+                let mut column = self.column(command.a);
+                column.rotate_right(command.b % self.height);
+                self.set_column(command.a, column);
             }
+            Operation::ShiftRow => shift(&mut self.grid[command.a], command.b),
+            Operation::ShiftColumn => {
+                let mut column = self.column(command.a);
+                shift(&mut column, command.b);
+                self.set_column(command.a, column);
+            }
+            Operation::FlipHorizontal => self.grid.iter_mut().for_each(|row| row.reverse()),
+            Operation::FlipVertical => self.grid.reverse(),
         }
+        Ok(())
     }
 
     fn lit(&self) -> usize {
         let mut count = 0;
-        for col in self.grid {
+        for col in &self.grid {
             for cell in col {
                 if cell.on() {
                     count += 1;
@@ -122,43 +257,148 @@ impl Display {
     }
 
     fn render(&self) {
-        for col in self.grid {
+        print!("{}", self);
+    }
+
+    fn pixels(&self) -> Vec<Vec<bool>> {
+        self.grid
+            .iter()
+            .map(|row| row.iter().map(State::on).collect())
+            .collect()
+    }
+
+    fn read(&self) -> Result<String, OcrError> {
+        ocr::recognize(&self.pixels())
+    }
+}
+
+impl fmt::Display for Display {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for col in &self.grid {
             for cell in col {
                 if cell.on() {
-                    print!("#");
+                    write!(f, "#")?;
                 } else {
-                    print!(".");
+                    write!(f, ".")?;
                 }
             }
-            print!("\n");
+            writeln!(f)?;
         }
+        Ok(())
     }
 }
 
-fn generate_display(input: &str) -> Display {
-    let mut display = Display::new();
+fn replay<F: FnMut(&Display)>(
+    input: &str,
+    width: usize,
+    height: usize,
+    mut each: F,
+) -> Result<Display, ScreenError> {
+    let mut display = Display::new(width, height);
     let mut commands = Vec::new();
     for line in input.lines() {
-        commands.push(Command::from(line));
+        commands.push(Command::from(line).map_err(ScreenError::BadCommand)?);
     }
     for command in commands {
-        display.execute(command);
+        display.execute(command)?;
+        each(&display);
     }
-    display
+    Ok(display)
+}
+
+fn generate_display(input: &str, width: usize, height: usize) -> Result<Display, ScreenError> {
+    replay(input, width, height, |_| {})
+}
+
+fn generate_history(input: &str, width: usize, height: usize) -> Result<Vec<Display>, ScreenError> {
+    let mut frames = Vec::new();
+    replay(input, width, height, |display| frames.push(display.clone()))?;
+    Ok(frames)
 }
 
 fn part1(input: &str) -> usize {
-    generate_display(input).lit()
+    generate_display(input, WIDTH, HEIGHT)
+        .expect("commands should fit the screen")
+        .lit()
 }
 
-fn part2(input: &str) {
-    generate_display(input).render()
+fn part2(input: &str) -> Result<String, OcrError> {
+    generate_display(input, WIDTH, HEIGHT)
+        .expect("commands should fit the screen")
+        .read()
 }
 
 fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    if args.first().map(String::as_str) == Some("compile") && args.len() == 2 {
+        let target = match fs::read_to_string(&args[1]) {
+            Ok(art) => {
+                let width = art
+                    .lines()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                art.lines()
+                    .map(|line| {
+                        let mut row = line.chars().map(|c| c == '#').collect::<Vec<bool>>();
+                        row.resize(width, false);
+                        row
+                    })
+                    .collect()
+            }
+            Err(_) => match ocr::draw(&args[1]) {
+                Ok(target) => target,
+                Err(c) => {
+                    println!("{:?} has no glyph", c);
+                    return;
+                }
+            },
+        };
+        match compile::compile(&target) {
+            Ok(commands) if compile::verify(&target, &commands) => {
+                for command in commands {
+                    println!("{}", command);
+                }
+            }
+            Ok(_) => println!("synthesized commands do not reproduce the target"),
+            Err(err) => println!("{:?}", err),
+        }
+        return;
+    }
     let input = fs::read_to_string("input.txt").expect("input.txt should exist");
+    let value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|idx| args.get(idx + 1))
+    };
+    let scale =
+        value("--scale").map_or(1, |scale| scale.parse().expect("scale should be a number"));
+    if value("--frames").is_some() || value("--gif").is_some() {
+        let frames =
+            generate_history(&input, WIDTH, HEIGHT).expect("commands should fit the screen");
+        if let Some(dir) = value("--frames") {
+            match export::write_frames(&frames, Path::new(dir), scale) {
+                Ok(paths) => println!("wrote {} frames to {}", paths.len(), dir),
+                Err(err) => println!("{}", err),
+            }
+        }
+        if let Some(path) = value("--gif") {
+            match export::write_gif(&frames, Path::new(path), scale, 5) {
+                Ok(()) => println!("wrote {} frames to {}", frames.len(), path),
+                Err(err) => println!("{}", err),
+            }
+        }
+    }
     println!("{}", part1(&input));
-    part2(&input);
+    match part2(&input) {
+        Ok(text) => println!("{}", text),
+        Err(err) => {
+            println!("{:?}", err);
+            generate_display(&input, WIDTH, HEIGHT)
+                .expect("commands should fit the screen")
+                .render();
+        }
+    }
 }
 
 #[cfg(test)]
@@ -170,4 +410,223 @@ mod tests {
         let input = fs::read_to_string("test.txt").expect("test.txt should exist");
         assert_eq!(0, part1(&input));
     }
+
+    const EXAMPLE: &str =
+        "rect 3x2\nrotate column x=1 by 1\nrotate row y=0 by 4\nrotate column x=1 by 1";
+
+    #[test]
+    fn test_small_screen() {
+        let display = generate_display(EXAMPLE, 7, 3).unwrap();
+        assert_eq!(6, display.lit());
+        assert_eq!(".#..#.#\n#.#....\n.#.....\n", display.to_string());
+    }
+
+    #[test]
+    fn test_read() {
+        let input =
+            "rect 4x1\nrotate column x=1 by 5\nrotate column x=2 by 5\nrotate column x=3 by 5\nrect 1x6";
+        let display = generate_display(input, 5, 6).unwrap();
+        assert_eq!(Ok("L".to_string()), display.read());
+        assert_eq!(
+            Err(OcrError::UnknownGlyph(0)),
+            generate_display(EXAMPLE, 7, 6).unwrap().read()
+        );
+        assert_eq!(
+            Err(OcrError::WrongHeight(3)),
+            generate_display(EXAMPLE, 7, 3).unwrap().read()
+        );
+    }
+
+    fn shift_once(display: &mut Display, command: Command) {
+        for _ in 0..command.b {
+            match command.operation {
+                Operation::RotateRow => {
+                    let row = &mut display.grid[command.a];
+                    let old_edge = row[display.width - 1];
+                    for x in (1..display.width).rev() {
+                        row[x] = row[x - 1];
+                    }
+                    row[0] = old_edge;
+                }
+                Operation::RotateColumn => {
+                    let col = command.a;
+                    let old_edge = display.grid[display.height - 1][col];
+                    for y in (1..display.height).rev() {
+                        display.grid[y][col] = display.grid[y - 1][col];
+                    }
+                    display.grid[0][col] = old_edge;
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rotations() {
+        let start = generate_display("rect 3x2\nrotate row y=1 by 2\nrect 1x3", 7, 3).unwrap();
+        for operation in [Operation::RotateRow, Operation::RotateColumn] {
+            let lines = if operation == Operation::RotateRow {
+                3
+            } else {
+                7
+            };
+            for a in 0..lines {
+                for b in [0, 1, 2, 3, 6, 7, 8, 20, 21, 100] {
+                    let command = Command::new(operation, a, b);
+                    let mut expected = start.clone();
+                    shift_once(&mut expected, command);
+                    let mut display = start.clone();
+                    display.execute(command).unwrap();
+                    assert_eq!(expected, display, "{:?}", command);
+                }
+            }
+        }
+        let mut display = start.clone();
+        display
+            .execute(Command::from("rotate row y=0 by 7").unwrap())
+            .unwrap();
+        display
+            .execute(Command::from("rotate column x=0 by 3").unwrap())
+            .unwrap();
+        assert_eq!(start, display);
+    }
+
+    #[test]
+    fn test_command_display() {
+        for line in EXAMPLE.lines() {
+            assert_eq!(line, Command::from(line).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn test_operations() {
+        let display = |input| generate_display(input, 7, 3).unwrap().to_string();
+        assert_eq!(".......\n..##...\n..##...\n", display("rect 2x2 at 2,1"));
+        assert_eq!(
+            "###....\n#.#....\n###....\n",
+            display("rect 3x3\nrect-off 1x1 at 1,1")
+        );
+        assert_eq!(
+            "..#....\n##.....\n.......\n",
+            display("rect 2x2\ninvert 3x1")
+        );
+        assert_eq!(
+            "..###..\n.......\n.......\n",
+            display("rect 3x1\nshift row y=0 by 2")
+        );
+        assert_eq!(
+            ".......\n.......\n.......\n",
+            display("rect 3x1\nshift row y=0 by 9")
+        );
+        assert_eq!(
+            ".......\n#......\n#......\n",
+            display("rect 1x2\nshift column x=0 by 1")
+        );
+        assert_eq!(
+            ".....#.\n.....##\n.......\n",
+            display("rect 2x2\nrect-off 1x1\nflip horizontal")
+        );
+        assert_eq!(
+            ".......\n.#.....\n##.....\n",
+            display("rect 2x2\nrect-off 1x1 at 0,1\nflip vertical")
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        for line in [
+            "rect 2x3 at 4,1",
+            "rect-off 1x1",
+            "invert 3x2 at 1,0",
+            "shift row y=2 by 3",
+            "shift column x=4 by 1",
+            "flip horizontal",
+            "flip vertical",
+        ] {
+            assert_eq!(line, Command::from(line).unwrap().to_string());
+        }
+        assert_eq!(
+            Command::from("rect 2x3").unwrap(),
+            Command::from("rect 2x3 at 0,0").unwrap()
+        );
+        assert_eq!(
+            Err(CommandError::UnknownVerb("blink".to_string())),
+            Command::from("blink 2x3")
+        );
+        for line in [
+            "rect 2y3",
+            "rect 2x3 at 1",
+            "rotate diagonal x=1 by 2",
+            "flip",
+            "",
+        ] {
+            assert_eq!(
+                Err(CommandError::Malformed(line.to_string())),
+                Command::from(line)
+            );
+        }
+        assert_eq!(
+            Err(ScreenError::BadCommand(CommandError::UnknownVerb(
+                "spin".to_string()
+            ))),
+            generate_display("rect 1x1\nspin 3", 7, 3)
+        );
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut display = Display::new(7, 3);
+        assert_eq!(
+            Err(ScreenError::RectTooWide(8)),
+            display.execute(Command::from("rect 8x1").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RectTooTall(4)),
+            display.execute(Command::from("rect 1x4").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RowOutOfRange(3)),
+            display.execute(Command::from("rotate row y=3 by 1").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::ColumnOutOfRange(7)),
+            display.execute(Command::from("rotate column x=7 by 1").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RectTooWide(8)),
+            display.execute(Command::from("invert 2x1 at 6,0").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RectTooTall(4)),
+            display.execute(Command::from("rect-off 1x2 at 0,2").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RowOutOfRange(3)),
+            display.execute(Command::from("shift row y=3 by 1").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RectTooWide(usize::MAX)),
+            display.execute(Command::from("rect 1x1 at 18446744073709551615,0").unwrap())
+        );
+        assert_eq!(
+            Err(ScreenError::RectTooTall(usize::MAX)),
+            display.execute(Command::from("invert 1x18446744073709551615 at 0,1").unwrap())
+        );
+        assert_eq!(0, display.lit());
+        assert_eq!(Ok(()), display.execute(Command::from("rect 7x3").unwrap()));
+        for (width, height) in [(0, 3), (7, 0), (0, 0)] {
+            for line in [
+                "rotate row y=0 by 1",
+                "rotate column x=0 by 1",
+                "rect 0x0",
+                "flip vertical",
+            ] {
+                assert_eq!(
+                    Err(ScreenError::EmptyScreen(width, height)),
+                    generate_display(line, width, height)
+                );
+            }
+        }
+        assert_eq!(21, display.lit());
+    }
 }
@@ -0,0 +1,101 @@
+pub const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 6;
+
+const GLYPHS: [(char, [&str; GLYPH_HEIGHT]); 19] = [
+    ('A', [".##..", "#..#.", "#..#.", "####.", "#..#.", "#..#."]),
+    ('B', ["###..", "#..#.", "###..", "#..#.", "#..#.", "###.."]),
+    ('C', [".##..", "#..#.", "#....", "#....", "#..#.", ".##.."]),
+    ('E', ["####.", "#....", "###..", "#....", "#....", "####."]),
+    ('F', ["####.", "#....", "###..", "#....", "#....", "#...."]),
+    ('G', [".##..", "#..#.", "#....", "#.##.", "#..#.", ".###."]),
+    ('H', ["#..#.", "#..#.", "####.", "#..#.", "#..#.", "#..#."]),
+    ('I', [".###.", "..#..", "..#..", "..#..", "..#..", ".###."]),
+    ('J', ["..##.", "...#.", "...#.", "...#.", "#..#.", ".##.."]),
+    ('K', ["#..#.", "#.#..", "##...", "#.#..", "#.#..", "#..#."]),
+    ('L', ["#....", "#....", "#....", "#....", "#....", "####."]),
+    ('O', [".##..", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+    ('P', ["###..", "#..#.", "#..#.", "###..", "#....", "#...."]),
+    ('R', ["###..", "#..#.", "#..#.", "###..", "#.#..", "#..#."]),
+    ('S', [".###.", "#....", "#....", ".##..", "...#.", "###.."]),
+    ('U', ["#..#.", "#..#.", "#..#.", "#..#.", "#..#.", ".##.."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####.", "...#.", "..#..", ".#...", "#....", "####."]),
+    (' ', [".....", ".....", ".....", ".....", ".....", "....."]),
+];
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum OcrError {
+    WrongHeight(usize),
+    UnknownGlyph(usize),
+}
+
+fn pixel(rows: &[Vec<bool>], x: usize, y: usize) -> bool {
+    rows[y].get(x).copied().unwrap_or(false)
+}
+
+fn matches(rows: &[Vec<bool>], left: usize, glyph: &[&str; GLYPH_HEIGHT]) -> bool {
+    glyph.iter().enumerate().all(|(y, line)| {
+        line.bytes()
+            .enumerate()
+            .all(|(x, c)| (c == b'#') == pixel(rows, left + x, y))
+    })
+}
+
+pub fn recognize(rows: &[Vec<bool>]) -> Result<String, OcrError> {
+    if rows.len() != GLYPH_HEIGHT {
+        return Err(OcrError::WrongHeight(rows.len()));
+    }
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut retval = String::new();
+    for (idx, left) in (0..width).step_by(GLYPH_WIDTH).enumerate() {
+        let glyph = GLYPHS
+            .iter()
+            .find(|(_, glyph)| matches(rows, left, glyph))
+            .ok_or(OcrError::UnknownGlyph(idx))?;
+        retval.push(glyph.0);
+    }
+    Ok(retval.trim_end().to_string())
+}
+
+pub fn draw(text: &str) -> Result<Vec<Vec<bool>>, char> {
+    let mut rows = vec![Vec::new(); GLYPH_HEIGHT];
+    for c in text.chars() {
+        let (_, glyph) = GLYPHS.iter().find(|(letter, _)| *letter == c).ok_or(c)?;
+        for (row, line) in rows.iter_mut().zip(glyph.iter()) {
+            row.extend(line.bytes().map(|b| b == b'#'));
+        }
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recognize() {
+        let alphabet = GLYPHS.iter().map(|(c, _)| *c).collect::<String>();
+        assert_eq!(
+            Ok(alphabet.trim_end().to_string()),
+            recognize(&draw(&alphabet).unwrap())
+        );
+        assert_eq!(
+            Ok("ZJHRKCPLYJ".to_string()),
+            recognize(&draw("ZJHRKCPLYJ").unwrap())
+        );
+        let mut rows = draw("HI").unwrap();
+        for row in rows.iter_mut() {
+            row.truncate(9);
+        }
+        assert_eq!(Ok("HI".to_string()), recognize(&rows));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rows = draw("ABC").unwrap();
+        rows[0][GLYPH_WIDTH + 4] = true;
+        assert_eq!(Err(OcrError::UnknownGlyph(1)), recognize(&rows));
+        assert_eq!(Err(OcrError::WrongHeight(5)), recognize(&rows[..5]));
+        assert_eq!(Err('D'), draw("ADA"));
+    }
+}
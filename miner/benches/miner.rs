@@ -0,0 +1,64 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use md5::{Digest, Md5};
+use miner::{encode_hex, Algorithm, Difficulty, Miner};
+
+const RANGE: usize = 20000;
+
+fn hex_string(prefix: &str) -> usize {
+    let mut count = 0;
+    for num in 0..RANGE {
+        let hash = Md5::digest(format!("{}{}", prefix, num))
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        let chars = hash.chars().collect::<Vec<char>>();
+        if chars[..5].iter().all(|c| *c == '0') {
+            count += 1;
+        }
+    }
+    count
+}
+
+fn bytes(prefix: &str) -> usize {
+    let miner = Miner::new(prefix, Algorithm::Md5, Difficulty::Nibbles(5), 0..RANGE);
+    (0..RANGE).filter(|idx| miner.mine(*idx).is_some()).count()
+}
+
+fn stretch_hex_string(input: &str) -> String {
+    let mut hash = Md5::digest(input)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>();
+    for _ in 0..2016 {
+        hash = Md5::digest(&hash)
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+    }
+    hash
+}
+
+fn stretch_bytes(input: &str) -> [u8; 32] {
+    let mut hex = [0; 32];
+    encode_hex(&Md5::digest(input), &mut hex);
+    for _ in 0..2016 {
+        let digest = Md5::digest(hex);
+        encode_hex(&digest, &mut hex);
+    }
+    hex
+}
+
+fn bench(c: &mut Criterion) {
+    let mut group = c.benchmark_group("md5 five zero nibbles");
+    group.bench_function("hex string", |b| b.iter(|| hex_string("abc")));
+    group.bench_function("raw bytes", |b| b.iter(|| bytes("abc")));
+    group.finish();
+
+    let mut group = c.benchmark_group("md5 key stretching");
+    group.bench_function("hex string", |b| b.iter(|| stretch_hex_string("abc0")));
+    group.bench_function("raw bytes", |b| b.iter(|| stretch_bytes("abc0")));
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,290 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use md5::Md5;
+use rayon::prelude::*;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+const BATCH: usize = 100000;
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Algorithm {
+    Md5,
+    Sha1,
+    Sha256,
+}
+
+impl Algorithm {
+    pub fn digest(&self, input: &[u8]) -> Vec<u8> {
+        match self {
+            Algorithm::Md5 => Md5::digest(input).to_vec(),
+            Algorithm::Sha1 => Sha1::digest(input).to_vec(),
+            Algorithm::Sha256 => Sha256::digest(input).to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Prefix {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+}
+
+impl Prefix {
+    fn new(algorithm: Algorithm, prefix: &[u8]) -> Self {
+        match algorithm {
+            Algorithm::Md5 => Prefix::Md5(Md5::new_with_prefix(prefix)),
+            Algorithm::Sha1 => Prefix::Sha1(Sha1::new_with_prefix(prefix)),
+            Algorithm::Sha256 => Prefix::Sha256(Sha256::new_with_prefix(prefix)),
+        }
+    }
+
+    fn mine(&self, suffix: &[u8], difficulty: Difficulty) -> Option<Vec<u8>> {
+        match self {
+            Prefix::Md5(state) => finish(state, suffix, difficulty),
+            Prefix::Sha1(state) => finish(state, suffix, difficulty),
+            Prefix::Sha256(state) => finish(state, suffix, difficulty),
+        }
+    }
+}
+
+fn finish<D: Digest + Clone>(state: &D, suffix: &[u8], difficulty: Difficulty) -> Option<Vec<u8>> {
+    let digest = state.clone().chain_update(suffix).finalize();
+    if difficulty.check(&digest) {
+        Some(digest.to_vec())
+    } else {
+        None
+    }
+}
+
+pub fn encode_hex(digest: &[u8], out: &mut [u8]) {
+    const HEX: &[u8; 16] = b"0123456789abcdef";
+    for (idx, byte) in digest.iter().enumerate() {
+        out[idx * 2] = HEX[(byte >> 4) as usize];
+        out[idx * 2 + 1] = HEX[(byte & 0x0f) as usize];
+    }
+}
+
+pub fn decimal(mut num: usize, buf: &mut [u8; 20]) -> &[u8] {
+    let mut idx = buf.len();
+    loop {
+        idx -= 1;
+        buf[idx] = b'0' + (num % 10) as u8;
+        num /= 10;
+        if num == 0 {
+            return &buf[idx..];
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Difficulty {
+    Nibbles(usize),
+    Bits(usize),
+}
+
+impl Difficulty {
+    pub fn check(&self, digest: &[u8]) -> bool {
+        let bits = match self {
+            Difficulty::Nibbles(nibbles) => nibbles.saturating_mul(4),
+            Difficulty::Bits(bits) => *bits,
+        };
+        if bits > digest.len() * 8 {
+            return false;
+        }
+        if digest[..bits / 8].iter().any(|b| *b != 0) {
+            return false;
+        }
+        bits.is_multiple_of(8) || digest[bits / 8] >> (8 - bits % 8) == 0
+    }
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Hit {
+    pub index: usize,
+    pub digest: Vec<u8>,
+}
+
+impl Hit {
+    pub fn nibble(&self, idx: usize) -> u8 {
+        let byte = self.digest[idx / 2];
+        if idx.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0x0f
+        }
+    }
+
+    pub fn hex(&self) -> String {
+        let mut out = vec![0; self.digest.len() * 2];
+        encode_hex(&self.digest, &mut out);
+        String::from_utf8(out).unwrap()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Miner {
+    prefix: Prefix,
+    difficulty: Difficulty,
+    range: Range<usize>,
+    batch: usize,
+    hits: VecDeque<Hit>,
+}
+
+impl Miner {
+    pub fn new(
+        prefix: &str,
+        algorithm: Algorithm,
+        difficulty: Difficulty,
+        range: Range<usize>,
+    ) -> Self {
+        Miner {
+            prefix: Prefix::new(algorithm, prefix.as_bytes()),
+            difficulty,
+            range,
+            batch: BATCH,
+            hits: VecDeque::new(),
+        }
+    }
+
+    pub fn with_batch(mut self, batch: usize) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    pub fn mine(&self, index: usize) -> Option<Hit> {
+        let mut buf = [0; 20];
+        let digest = self
+            .prefix
+            .mine(decimal(index, &mut buf), self.difficulty)?;
+        Some(Hit { index, digest })
+    }
+
+    pub fn next_index(&self) -> usize {
+        self.range.start
+    }
+}
+
+impl Iterator for Miner {
+    type Item = Hit;
+
+    fn next(&mut self) -> Option<Hit> {
+        while self.hits.is_empty() && !self.range.is_empty() {
+            let stop = (self.range.start + self.batch).min(self.range.end);
+            let hits = (self.range.start..stop)
+                .into_par_iter()
+                .filter_map(|idx| self.mine(idx))
+                .collect::<Vec<Hit>>();
+            self.hits.extend(hits);
+            self.range.start = stop;
+        }
+        self.hits.pop_front()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digest() {
+        assert_eq!(
+            "900150983cd24fb0d6963f7d28e17f72",
+            Hit {
+                index: 0,
+                digest: Algorithm::Md5.digest(b"abc")
+            }
+            .hex()
+        );
+        assert_eq!(
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+            Hit {
+                index: 0,
+                digest: Algorithm::Sha1.digest(b"abc")
+            }
+            .hex()
+        );
+        assert_eq!(
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            Hit {
+                index: 0,
+                digest: Algorithm::Sha256.digest(b"abc")
+            }
+            .hex()
+        );
+    }
+
+    #[test]
+    fn test_decimal() {
+        let mut buf = [0; 20];
+        assert_eq!(b"0", decimal(0, &mut buf));
+        assert_eq!(b"3231929", decimal(3231929, &mut buf));
+        assert_eq!(
+            usize::MAX.to_string().as_bytes(),
+            decimal(usize::MAX, &mut buf)
+        );
+    }
+
+    #[test]
+    fn test_difficulty() {
+        assert!(Difficulty::Nibbles(5).check(&[0x00, 0x00, 0x0f, 0xff]));
+        assert!(!Difficulty::Nibbles(5).check(&[0x00, 0x00, 0x10, 0x00]));
+        assert!(Difficulty::Bits(20).check(&[0x00, 0x00, 0x0f, 0xff]));
+        assert!(!Difficulty::Bits(21).check(&[0x00, 0x00, 0x0f, 0xff]));
+        assert!(Difficulty::Bits(0).check(&[0xff]));
+        assert!(Difficulty::Bits(8).check(&[0x00]));
+        assert!(!Difficulty::Bits(9).check(&[0x00]));
+        assert!(!Difficulty::Nibbles(usize::MAX).check(&[0x00]));
+        assert!(!Difficulty::Nibbles(usize::MAX / 2).check(&[0x00]));
+        assert!(!Difficulty::Bits(usize::MAX).check(&[0x00]));
+    }
+
+    #[test]
+    fn test_miner() {
+        let hits = Miner::new(
+            "abc",
+            Algorithm::Md5,
+            Difficulty::Nibbles(5),
+            3231929..3231930,
+        )
+        .collect::<Vec<Hit>>();
+        assert_eq!(1, hits.len());
+        assert_eq!(3231929, hits[0].index);
+        assert_eq!("00000155f8105dff7f56ee10fa9b9abd", hits[0].hex());
+        assert_eq!(1, hits[0].nibble(5));
+        assert_eq!(5, hits[0].nibble(6));
+    }
+
+    #[test]
+    fn test_miner_matches_sequential() {
+        for algorithm in [Algorithm::Md5, Algorithm::Sha1, Algorithm::Sha256] {
+            let expected = (0..5000)
+                .filter(|idx| {
+                    let hex = Hit {
+                        index: *idx,
+                        digest: algorithm.digest(format!("xyz{}", idx).as_bytes()),
+                    }
+                    .hex();
+                    hex.starts_with("00")
+                })
+                .collect::<Vec<usize>>();
+            let found = Miner::new("xyz", algorithm, Difficulty::Nibbles(2), 0..5000)
+                .with_batch(333)
+                .map(|hit| hit.index)
+                .collect::<Vec<usize>>();
+            assert!(!expected.is_empty());
+            assert_eq!(expected, found);
+        }
+    }
+
+    #[test]
+    fn test_miner_stops_early() {
+        let mut miner =
+            Miner::new("xyz", Algorithm::Md5, Difficulty::Bits(4), 0..1000000).with_batch(100);
+        let first = miner.next().unwrap();
+        assert!(first.index < 100);
+        assert_eq!(100, miner.next_index());
+    }
+}